@@ -0,0 +1,325 @@
+//! Rendering changelogs from typed commits
+
+use git::Commit;
+use {BumpLevel, CommitType, TypeDefinition};
+
+/// The commit types included in a changelog by default, everything but `Meta`
+pub const DEFAULT_TYPES: &[CommitType] = &[CommitType::Breaking, CommitType::Feature, CommitType::Bugfix, CommitType::Other];
+
+/// Return the abbreviated form of a commit id
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(7)]
+}
+
+/// Render a Markdown changelog section for a release
+///
+/// Commits are grouped under one heading per commit type, in the order of the given types. Only the
+/// given types are included, and types without any commits are left out. Commits that are breaking
+/// changes because of a trailer are marked as such, as their heading doesn't say so.
+pub fn markdown(title: &str, commits: &[Commit], types: &[TypeDefinition]) -> String {
+    let mut output = format!("## {}\n", title);
+
+    for definition in types.iter() {
+        let mut group = commits.iter().filter(|commit| commit.definition.name == definition.name).peekable();
+
+        if group.peek().is_none() {
+            continue;
+        }
+
+        let heading = if definition.description.is_empty() { &definition.name } else { &definition.description };
+
+        output.push_str(&format!("\n### {} {}\n\n", definition.emoji, heading));
+
+        for commit in group {
+            if commit.is_breaking_change() && commit.commit_type() != Some(CommitType::Breaking) {
+                output.push_str(&format!("- **Breaking:** {} ({})\n", commit.subject, short_id(&commit.id)));
+            } else {
+                output.push_str(&format!("- {} ({})\n", commit.subject, short_id(&commit.id)));
+            }
+        }
+    }
+
+    output
+}
+
+/// A section of a release in the Keep a Changelog format
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Section {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+}
+
+const SECTIONS: [Section; 6] = [Section::Added, Section::Changed, Section::Deprecated, Section::Removed, Section::Fixed, Section::Security];
+
+impl Section {
+    /// Return the heading of this section
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Section::Added => "Added",
+            Section::Changed => "Changed",
+            Section::Deprecated => "Deprecated",
+            Section::Removed => "Removed",
+            Section::Fixed => "Fixed",
+            Section::Security => "Security",
+        }
+    }
+
+    /// Return the section a commit belongs in
+    ///
+    /// Breaking changes whose subject starts with the word "Remove" or "Drop" are listed as removed,
+    /// other breaking changes, cleanups and meta commits as changed. Configured types go in the
+    /// section with the same name, e.g. "Security", or otherwise by their bump level.
+    pub fn for_commit(commit: &Commit) -> Section {
+        let first_word = commit.subject.split_whitespace().next().unwrap_or("");
+
+        match commit.commit_type() {
+            Some(CommitType::Breaking) if first_word == "Remove" || first_word == "Drop" => Section::Removed,
+            Some(CommitType::Breaking) => Section::Changed,
+            Some(CommitType::Feature) => Section::Added,
+            Some(CommitType::Bugfix) => Section::Fixed,
+            Some(CommitType::Other) | Some(CommitType::Meta) => Section::Changed,
+            None => SECTIONS.iter().cloned().find(|section| section.name().eq_ignore_ascii_case(&commit.definition.name)).unwrap_or(match commit.definition.bump_level {
+                BumpLevel::Minor => Section::Added,
+                BumpLevel::Patch => Section::Fixed,
+                BumpLevel::Major | BumpLevel::None => Section::Changed,
+            }),
+        }
+    }
+}
+
+/// Render a release in the Keep a Changelog format
+///
+/// Only commits of the given types are included, see `Section::for_commit` for where they are listed.
+pub fn keep_a_changelog(version: &str, date: &str, commits: &[Commit], types: &[TypeDefinition]) -> String {
+    let mut output = format!("## [{}] - {}\n", version, date);
+    let included = |commit: &&Commit| types.iter().any(|definition| definition.name == commit.definition.name);
+
+    for section in SECTIONS.iter() {
+        let mut group = commits.iter().filter(included).filter(|commit| Section::for_commit(commit) == *section).peekable();
+
+        if group.peek().is_none() {
+            continue;
+        }
+
+        output.push_str(&format!("\n### {}\n\n", section.name()));
+
+        for commit in group {
+            if commit.is_breaking_change() {
+                output.push_str(&format!("- **Breaking:** {}\n", commit.subject));
+            } else {
+                output.push_str(&format!("- {}\n", commit.subject));
+            }
+        }
+    }
+
+    output
+}
+
+/// Check if a line is a release heading, other than the one for unreleased changes
+fn is_release_heading(line: &str) -> bool {
+    line.starts_with("## ") && !line[3..].trim().eq_ignore_ascii_case("[unreleased]")
+}
+
+/// Check if a line is a Markdown link reference definition, such as `[1.0.0]: https://...`
+fn is_link_definition(line: &str) -> bool {
+    line.starts_with('[') && line.contains("]: ")
+}
+
+/// Insert a rendered release into an existing changelog, above the most recent release
+///
+/// The title, the unreleased section and all older releases are kept as they are. Without any
+/// earlier release, the new one is added above the link definitions at the end of the file.
+pub fn insert_release(changelog: &str, release: &str) -> String {
+    if changelog.trim().is_empty() {
+        return format!("# Changelog\n\n{}", release);
+    }
+
+    let mut offset = 0;
+    let mut links = None;
+
+    for line in changelog.split_inclusive('\n') {
+        if is_release_heading(line) {
+            return format!("{}{}\n{}", &changelog[..offset], release, &changelog[offset..]);
+        }
+
+        if is_link_definition(line) {
+            links = links.or(Some(offset));
+        } else if !line.trim().is_empty() {
+            links = None;
+        }
+
+        offset += line.len();
+    }
+
+    let (before, after) = changelog.split_at(links.unwrap_or(changelog.len()));
+    let before = before.trim_end();
+
+    if after.is_empty() {
+        format!("{}\n\n{}", before, release)
+    } else {
+        format!("{}\n\n{}\n{}", before, release, after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{insert_release, keep_a_changelog, markdown, Section, DEFAULT_TYPES};
+    use git::Commit;
+    use {BumpLevel, CommitType, TypeDefinition};
+
+    fn commit(id: &str, commit_type: CommitType, subject: &str) -> Commit {
+        custom(id, TypeDefinition::from(commit_type), subject)
+    }
+
+    fn custom(id: &str, definition: TypeDefinition, subject: &str) -> Commit {
+        Commit {
+            id: String::from(id),
+            message: format!("{} {}\n", definition.emoji, subject),
+            definition,
+            subject: String::from(subject),
+        }
+    }
+
+    fn definitions(types: &[CommitType]) -> Vec<TypeDefinition> {
+        types.iter().cloned().map(TypeDefinition::from).collect()
+    }
+
+    fn security() -> TypeDefinition {
+        TypeDefinition { name: String::from("Security"), emoji: String::from("🔒"), description: String::new(), bump_level: BumpLevel::Patch, aliases: Vec::new() }
+    }
+
+    #[test]
+    fn it_groups_commits_by_type() {
+        let commits = vec![
+            commit("1111111111", CommitType::Bugfix, "Fix formatter"),
+            commit("2222222222", CommitType::Meta, "Update readme"),
+            commit("3333333333", CommitType::Feature, "Add formatter"),
+            commit("4444444444", CommitType::Bugfix, "Fix parser"),
+        ];
+
+        assert_eq!(markdown("1.3.0 (2026-10-15)", &commits, &definitions(DEFAULT_TYPES)), "## 1.3.0 (2026-10-15)
+
+### 🎉 New functionality
+
+- Add formatter (3333333)
+
+### 🐛 Bugfix
+
+- Fix formatter (1111111)
+- Fix parser (4444444)
+");
+    }
+
+    #[test]
+    fn it_maps_commits_to_sections() {
+        assert_eq!(Section::for_commit(&commit("1", CommitType::Breaking, "Remove parser")), Section::Removed);
+        assert_eq!(Section::for_commit(&commit("1", CommitType::Breaking, "Dropdown shows all types")), Section::Changed);
+        assert_eq!(Section::for_commit(&commit("1", CommitType::Breaking, "Removes the old parser")), Section::Changed);
+        assert_eq!(Section::for_commit(&commit("1", CommitType::Breaking, "Removed the old parser")), Section::Changed);
+        assert_eq!(Section::for_commit(&commit("1", CommitType::Breaking, "Rename parser")), Section::Changed);
+        assert_eq!(Section::for_commit(&commit("1", CommitType::Feature, "Add parser")), Section::Added);
+        assert_eq!(Section::for_commit(&commit("1", CommitType::Bugfix, "Fix parser")), Section::Fixed);
+        assert_eq!(Section::for_commit(&commit("1", CommitType::Other, "Speed up parser")), Section::Changed);
+        assert_eq!(Section::for_commit(&commit("1", CommitType::Meta, "Update readme")), Section::Changed);
+        assert_eq!(Section::for_commit(&custom("1", security(), "Escape input")), Section::Security);
+        assert_eq!(Section::for_commit(&custom("1", TypeDefinition { name: String::from("Docs"), ..security() }, "Document parser")), Section::Fixed);
+    }
+
+    #[test]
+    fn it_renders_keep_a_changelog_releases() {
+        let commits = vec![
+            commit("1111111111", CommitType::Bugfix, "Fix formatter"),
+            commit("2222222222", CommitType::Meta, "Update readme"),
+            commit("3333333333", CommitType::Breaking, "Drop old parser"),
+            commit("4444444444", CommitType::Feature, "Add formatter"),
+            commit("5555555555", CommitType::Other, "Speed up parser"),
+        ];
+
+        assert_eq!(keep_a_changelog("2.0.0", "2026-10-15", &commits, &definitions(DEFAULT_TYPES)), "## [2.0.0] - 2026-10-15
+
+### Added
+
+- Add formatter
+
+### Changed
+
+- Speed up parser
+
+### Removed
+
+- **Breaking:** Drop old parser
+
+### Fixed
+
+- Fix formatter
+");
+    }
+
+    #[test]
+    fn it_marks_breaking_change_trailers() {
+        let mut fix = commit("1111111111", CommitType::Bugfix, "Fix formatter");
+
+        fix.message.push_str("\nBREAKING CHANGE: output differs\n");
+
+        assert_eq!(keep_a_changelog("2.0.0", "2026-10-15", &[fix.clone()], &definitions(DEFAULT_TYPES)), "## [2.0.0] - 2026-10-15\n\n### Fixed\n\n- **Breaking:** Fix formatter\n");
+        assert_eq!(markdown("2.0.0", &[fix], &definitions(DEFAULT_TYPES)), "## 2.0.0\n\n### 🐛 Bugfix\n\n- **Breaking:** Fix formatter (1111111)\n");
+    }
+
+    #[test]
+    fn it_inserts_a_release_above_older_ones() {
+        let changelog = "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2026-01-01\n\n### Added\n\n- Everything\n\n[1.0.0]: https://example.com/1.0.0\n";
+        let release = "## [1.1.0] - 2026-10-15\n\n### Fixed\n\n- Fix parser\n";
+
+        assert_eq!(insert_release(changelog, release), "# Changelog\n\n## [Unreleased]\n\n## [1.1.0] - 2026-10-15\n\n### Fixed\n\n- Fix parser\n\n## [1.0.0] - 2026-01-01\n\n### Added\n\n- Everything\n\n[1.0.0]: https://example.com/1.0.0\n");
+    }
+
+    #[test]
+    fn it_inserts_the_first_release() {
+        let release = "## [0.1.0] - 2026-10-15\n\n### Added\n\n- Everything\n";
+
+        assert_eq!(insert_release("", release), format!("# Changelog\n\n{}", release));
+        assert_eq!(insert_release("# Changelog\n", release), format!("# Changelog\n\n{}", release));
+        assert_eq!(insert_release("# Changelog\n\n[Unreleased]: https://example.com\n", release), format!("# Changelog\n\n{}\n[Unreleased]: https://example.com\n", release));
+    }
+
+    #[test]
+    fn it_includes_the_given_types() {
+        let commits = vec![
+            commit("2222222222", CommitType::Meta, "Update readme"),
+            commit("3333333333", CommitType::Breaking, "Drop parser"),
+        ];
+
+        assert_eq!(markdown("2.0.0", &commits, &definitions(&[CommitType::Breaking, CommitType::Meta])), "## 2.0.0
+
+### 💥 Breaking change
+
+- Drop parser (3333333)
+
+### 🌹 Meta
+
+- Update readme (2222222)
+");
+
+        assert_eq!(markdown("2.0.0", &[], &definitions(DEFAULT_TYPES)), "## 2.0.0\n");
+        assert_eq!(keep_a_changelog("2.0.0", "2026-10-15", &commits, &definitions(&[CommitType::Feature])), "## [2.0.0] - 2026-10-15\n");
+        assert_eq!(keep_a_changelog("2.0.0", "2026-10-15", &commits, &definitions(&[CommitType::Meta])), "## [2.0.0] - 2026-10-15\n\n### Changed\n\n- Update readme\n");
+    }
+
+    #[test]
+    fn it_includes_configured_types() {
+        let commits = vec![
+            custom("1111111111", security(), "Escape input"),
+            commit("2222222222", CommitType::Bugfix, "Fix parser"),
+        ];
+        let mut types = definitions(DEFAULT_TYPES);
+
+        types.push(security());
+
+        assert_eq!(markdown("1.2.4", &commits, &types), "## 1.2.4\n\n### 🐛 Bugfix\n\n- Fix parser (2222222)\n\n### 🔒 Security\n\n- Escape input (1111111)\n");
+        assert_eq!(keep_a_changelog("1.2.4", "2026-10-15", &commits, &types), "## [1.2.4] - 2026-10-15\n\n### Fixed\n\n- Fix parser\n\n### Security\n\n- Escape input\n");
+    }
+}
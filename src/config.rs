@@ -0,0 +1,289 @@
+//! Project configuration, read from a `.emoji-commit.toml` file
+//!
+//! ```toml
+//! # Extra commit types, see `CommitTypeSet`
+//! [[type]]
+//! name = "Security"
+//! emoji = "🔒"
+//! bump = "Patch"
+//!
+//! [changelog]
+//! types = ["Breaking", "Feature", "Bugfix", "Security"]
+//!
+//! # Change the bump level of existing types
+//! [bump]
+//! Other = "None"
+//!
+//! [version]
+//! initial-development = false
+//!
+//! [lint]
+//! max-subject-length = 50
+//! disable = ["imperative-mood"]
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use error::ConfigError;
+use lint::Rule;
+use toml::{self, Table, Value};
+use {BumpLevel, CommitType, CommitTypeSet, TypeDefinition, Version};
+
+/// The name of the configuration file
+pub const FILE_NAME: &str = ".emoji-commit.toml";
+
+/// The settings for linting commit messages
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LintConfig {
+    /// The maximum number of characters in the subject, after the emoji
+    pub max_subject_length: usize,
+    /// The ids of the rules that are turned off, see `lint::Rule::id`
+    pub disabled: Vec<String>,
+}
+
+impl Default for LintConfig {
+    fn default() -> LintConfig {
+        LintConfig { max_subject_length: 72, disabled: Vec::new() }
+    }
+}
+
+/// The configuration of a project
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Config {
+    /// The commit types used in the project
+    pub types: CommitTypeSet,
+    /// The names of the commit types included in changelogs, every type but `Meta` by default
+    pub changelog_types: Vec<String>,
+    /// Whether `Major` bumps the minor version, and `Minor` the patch version, while the major version is zero
+    pub initial_development: bool,
+    /// The settings for linting commit messages
+    pub lint: LintConfig,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            types: CommitTypeSet::default(),
+            changelog_types: default_changelog_types(&CommitTypeSet::default()),
+            initial_development: true,
+            lint: LintConfig::default(),
+        }
+    }
+}
+
+/// Return the names of the types in the set that are included in changelogs by default, all but `Meta`
+fn default_changelog_types(types: &CommitTypeSet) -> Vec<String> {
+    types.iter().filter(|definition| definition.commit_type() != Some(CommitType::Meta)).map(|definition| definition.name.clone()).collect()
+}
+
+/// Return the table value of the given key, if it is set
+fn table<'a>(root: &'a Table, key: &str) -> Result<Option<&'a Table>, ConfigError> {
+    match toml::get(root, key) {
+        None => Ok(None),
+        Some(Value::Table(table)) => Ok(Some(table)),
+        Some(_) => Err(ConfigError::new(None, format!("\"{}\" must be a table, e.g. [{}]", key, key))),
+    }
+}
+
+impl Config {
+    /// Parse the contents of a configuration file
+    pub fn parse(input: &str) -> Result<Config, ConfigError> {
+        let root = toml::parse(input)?;
+        let types = CommitTypeSet::from_table(&root)?;
+        let mut config = Config { changelog_types: default_changelog_types(&types), types, ..Config::default() };
+
+        if let Some(bump) = table(&root, "bump")? {
+            for (name, value) in bump.iter() {
+                let level = match value {
+                    Value::String(level) => level.parse::<BumpLevel>().map_err(|err| ConfigError::new(None, format!("bump: {}", err)))?,
+                    _ => return Err(ConfigError::new(None, format!("bump: \"{}\" must be a string", name))),
+                };
+
+                match config.types.get_mut(name) {
+                    Some(definition) => definition.bump_level = level,
+                    None => return Err(ConfigError::new(None, format!("bump: unknown commit type \"{}\"", name))),
+                }
+            }
+        }
+
+        if let Some(changelog) = table(&root, "changelog")? {
+            if let Some(names) = toml::strings(changelog, "types")? {
+                config.changelog_types = names.iter().map(|name| {
+                    match config.types.iter().find(|definition| definition.name.eq_ignore_ascii_case(name) || definition.matches(name)) {
+                        Some(definition) => Ok(definition.name.clone()),
+                        None => {
+                            let choices: Vec<&str> = config.types.iter().flat_map(|definition| vec![definition.emoji.as_str(), definition.name.as_str()]).collect();
+
+                            Err(ConfigError::new(None, format!("changelog: unknown commit type \"{}\", expected one of: {}", name, choices.join(", "))))
+                        }
+                    }
+                }).collect::<Result<Vec<String>, ConfigError>>()?;
+            }
+        }
+
+        if let Some(version) = table(&root, "version")? {
+            config.initial_development = toml::boolean(version, "initial-development")?.unwrap_or(true);
+        }
+
+        if let Some(lint) = table(&root, "lint")? {
+            match toml::get(lint, "max-subject-length") {
+                None => {}
+                Some(&Value::Integer(max)) if max > 0 => config.lint.max_subject_length = max as usize,
+                Some(_) => return Err(ConfigError::new(None, "\"max-subject-length\" must be a positive number")),
+            }
+
+            if let Some(disabled) = toml::strings(lint, "disable")? {
+                for id in disabled.iter() {
+                    id.parse::<Rule>().map_err(|err| ConfigError::new(None, format!("lint: {}", err)))?;
+                }
+
+                config.lint.disabled = disabled.into_iter().map(String::from).collect();
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Read a configuration file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+        let input = fs::read_to_string(path.as_ref()).map_err(|err| ConfigError::new(None, format!("{}: {}", path.as_ref().display(), err)))?;
+
+        Config::parse(&input)
+    }
+
+    /// Find the configuration file in the given directory or the closest of its parents
+    pub fn find<P: AsRef<Path>>(start: P) -> Option<PathBuf> {
+        start.as_ref().ancestors().map(|dir| dir.join(FILE_NAME)).find(|path| path.is_file())
+    }
+
+    /// Read the closest configuration file, or return the default configuration if there is none
+    pub fn discover<P: AsRef<Path>>(start: P) -> Result<Config, ConfigError> {
+        match Config::find(start) {
+            Some(path) => Config::load(path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    /// Return the bump level of a commit type, taking overrides into account
+    pub fn bump_level(&self, commit_type: CommitType) -> BumpLevel {
+        self.types.get(commit_type.name()).map_or(commit_type.bump_level(), |definition| definition.bump_level)
+    }
+
+    /// Return the definitions of the commit types included in changelogs, in the order of the type set
+    pub fn changelog_definitions(&self) -> Vec<TypeDefinition> {
+        self.types.iter().filter(|definition| self.changelog_types.iter().any(|name| name.eq_ignore_ascii_case(&definition.name))).cloned().collect()
+    }
+
+    /// Return the version that follows the given version at the given bump level
+    pub fn next_version(&self, level: BumpLevel, version: &Version) -> Version {
+        level.bump(version, self.initial_development)
+    }
+
+    /// Return the next pre-release version at the given bump level, see `BumpLevel::apply_prerelease`
+    pub fn next_prerelease(&self, level: BumpLevel, version: &Version, tag: &str) -> Version {
+        level.bump_prerelease(version, tag, self.initial_development)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use super::{Config, FILE_NAME};
+    use {BumpLevel, CommitType, Version};
+
+    #[test]
+    fn it_parses_a_config() {
+        let config = Config::parse("[[type]]\nname = \"Security\"\nemoji = \"🔒\"\nbump = \"Patch\"\n\n[changelog]\ntypes = [\"Breaking\", \"feature\"]\n\n[version]\ninitial-development = false\n\n[lint]\nmax-subject-length = 50\ndisable = [\"imperative-mood\"]\n").unwrap();
+
+        assert_eq!(config.types.get("Security").unwrap().bump_level, BumpLevel::Patch);
+        assert_eq!(config.changelog_types, vec!["Breaking", "Feature"]);
+        assert!(!config.initial_development);
+        assert_eq!(config.lint.max_subject_length, 50);
+        assert_eq!(config.lint.disabled, vec!["imperative-mood"]);
+    }
+
+    #[test]
+    fn it_defaults_missing_settings() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+        assert_eq!(Config::default().changelog_types, vec!["Breaking", "Feature", "Bugfix", "Other"]);
+        assert_eq!(Config::default().lint.max_subject_length, 72);
+    }
+
+    #[test]
+    fn it_includes_configured_types_in_changelogs() {
+        let security = "[[type]]\nname = \"Security\"\nemoji = \"🔒\"\n";
+        let config = Config::parse(security).unwrap();
+
+        assert_eq!(config.changelog_types, vec!["Breaking", "Feature", "Bugfix", "Other", "Security"]);
+
+        let config = Config::parse(&format!("{}\n[changelog]\ntypes = [\"security\", \"🐛\"]\n", security)).unwrap();
+
+        assert_eq!(config.changelog_types, vec!["Security", "Bugfix"]);
+        assert_eq!(config.changelog_definitions().iter().map(|definition| definition.name.as_str()).collect::<Vec<_>>(), vec!["Bugfix", "Security"]);
+    }
+
+    #[test]
+    fn it_overrides_bump_levels() {
+        let config = Config::parse("[[type]]\nname = \"Security\"\nemoji = \"🔒\"\n\n[bump]\nOther = \"None\"\nmeta = \"patch\"\nSecurity = \"Patch\"\n").unwrap();
+
+        assert_eq!(config.bump_level(CommitType::Other), BumpLevel::None);
+        assert_eq!(config.bump_level(CommitType::Meta), BumpLevel::Patch);
+        assert_eq!(config.bump_level(CommitType::Feature), BumpLevel::Minor);
+        assert_eq!(config.types.get("Security").unwrap().bump_level, BumpLevel::Patch);
+        assert_eq!(config.types.classify("🌹 Update docs").unwrap().0.bump_level, BumpLevel::Patch);
+    }
+
+    #[test]
+    fn it_rejects_invalid_bump_overrides() {
+        assert_eq!(Config::parse("[bump]\nDocs = \"Patch\"\n").unwrap_err().to_string(), "bump: unknown commit type \"Docs\"");
+        assert_eq!(Config::parse("[bump]\nOther = \"Tiny\"\n").unwrap_err().to_string(), "bump: unknown bump level \"Tiny\", expected one of: Major, Minor, Patch, None");
+        assert_eq!(Config::parse("[bump]\nOther = 1\n").unwrap_err().to_string(), "bump: \"Other\" must be a string");
+    }
+
+    #[test]
+    fn it_rejects_invalid_settings() {
+        assert_eq!(Config::parse("changelog = 1\n").unwrap_err().to_string(), "\"changelog\" must be a table, e.g. [changelog]");
+        assert_eq!(Config::parse("[changelog]\ntypes = [\"Docs\"]\n").unwrap_err().message(), "changelog: unknown commit type \"Docs\", expected one of: 💥, Breaking, 🎉, Feature, 🐛, Bugfix, 🔥, Other, 🌹, Meta");
+        assert!(Config::parse("[lint]\nmax-subject-length = 0\n").is_err());
+        assert!(Config::parse("[lint]\ndisable = [\"mood\"]\n").unwrap_err().message().starts_with("lint: unknown lint rule \"mood\""));
+        assert_eq!(Config::parse("[version\n").unwrap_err().line(), Some(1));
+    }
+
+    #[test]
+    fn it_applies_the_initial_development_policy() {
+        let mut config = Config::default();
+        let version = Version::new(0, 3, 1);
+
+        assert_eq!(config.next_version(BumpLevel::Major, &version).to_string(), "0.4.0");
+
+        config.initial_development = false;
+
+        assert_eq!(config.next_version(BumpLevel::Major, &version).to_string(), "1.0.0");
+        assert_eq!(config.next_version(BumpLevel::Minor, &version).to_string(), "0.4.0");
+        assert_eq!(config.next_prerelease(BumpLevel::Major, &version, "rc").to_string(), "1.0.0-rc.0");
+    }
+
+    #[test]
+    fn it_discovers_the_closest_config() {
+        let root = env::temp_dir().join(format!("emoji-commit-type-config-{}", std::process::id()));
+        let nested = root.join("a").join("b");
+
+        fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(Config::find(&nested).filter(|path| path.starts_with(&root)), None);
+
+        fs::write(root.join(FILE_NAME), "[lint]\nmax-subject-length = 60\n").unwrap();
+
+        assert_eq!(Config::find(&nested), Some(root.join(FILE_NAME)));
+        assert_eq!(Config::discover(&nested).unwrap().lint.max_subject_length, 60);
+
+        fs::write(root.join("a").join(FILE_NAME), "[lint]\nmax-subject-length = 40\n").unwrap();
+
+        assert_eq!(Config::discover(&nested).unwrap().lint.max_subject_length, 40);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+//! Translating between emoji commit messages and Conventional Commits
+
+use {CommitMessage, CommitType};
+
+/// The header of a Conventional Commits message, e.g. `feat(parser)!: add trailers`
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Header<'a> {
+    pub kind: &'a str,
+    pub scope: Option<&'a str>,
+    pub breaking: bool,
+    pub description: &'a str,
+}
+
+impl<'a> Header<'a> {
+    /// Parse the first line of a message as a Conventional Commits header
+    pub fn parse(message: &'a str) -> Option<Header<'a>> {
+        let line = message.lines().next()?;
+        let kind_len = line.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(line.len());
+        let (kind, mut rest) = line.split_at(kind_len);
+
+        if kind.is_empty() {
+            return None;
+        }
+
+        let scope = match rest.strip_prefix('(') {
+            Some(after) => {
+                let end = after.find(')')?;
+
+                if end == 0 {
+                    return None;
+                }
+
+                rest = &after[end + 1..];
+                Some(&after[..end])
+            }
+            None => None,
+        };
+
+        let breaking = rest.starts_with('!');
+        let description = rest[if breaking { 1 } else { 0 }..].strip_prefix(": ")?.trim();
+
+        if description.is_empty() {
+            return None;
+        }
+
+        Some(Header { kind, scope, breaking, description })
+    }
+}
+
+/// Return the commit type for a Conventional Commits type such as `feat` or `fix`
+pub fn commit_type(kind: &str) -> Option<CommitType> {
+    match kind.to_ascii_lowercase().as_str() {
+        "feat" => Some(CommitType::Feature),
+        "fix" => Some(CommitType::Bugfix),
+        "refactor" | "perf" | "style" | "revert" => Some(CommitType::Other),
+        "chore" | "docs" | "test" | "build" | "ci" => Some(CommitType::Meta),
+        _ => None,
+    }
+}
+
+/// Return the Conventional Commits type for a commit type, breaking changes use `feat!`
+pub fn kind(commit_type: CommitType) -> &'static str {
+    match commit_type {
+        CommitType::Breaking => "feat",
+        CommitType::Feature => "feat",
+        CommitType::Bugfix => "fix",
+        CommitType::Other => "refactor",
+        CommitType::Meta => "chore",
+    }
+}
+
+/// Parse the subject line of a message in either style, returning the commit type and the subject
+///
+/// Conventional Commits with a `!` are breaking changes. A `BREAKING CHANGE:` footer doesn't change
+/// the type, only the bump level, see `Commit::bump_level`.
+pub fn classify(message: &str) -> Option<(CommitType, &str)> {
+    if let Some(found) = CommitType::from_message(message) {
+        return Some(found);
+    }
+
+    let header = Header::parse(message)?;
+    let commit_type = commit_type(header.kind)?;
+
+    if header.breaking {
+        Some((CommitType::Breaking, header.description))
+    } else {
+        Some((commit_type, header.description))
+    }
+}
+
+/// Translate a Conventional Commits message into an emoji commit message
+///
+/// The scope is kept at the start of the subject, and the body and footers are kept as they are.
+pub fn from_conventional(message: &str) -> Option<CommitMessage> {
+    let header = Header::parse(message)?;
+    let (commit_type, _) = classify(message)?;
+    let rest = &message[message.find('\n').unwrap_or(message.len())..];
+
+    let subject = match header.scope {
+        Some(scope) => format!("{}: {}", scope, header.description),
+        None => String::from(header.description),
+    };
+
+    CommitMessage::parse(&format!("{} {}{}", commit_type.emoji(), subject, rest)).ok()
+}
+
+/// Translate an emoji commit message into a Conventional Commits message
+///
+/// A single word followed by a colon at the start of the subject, as written by `from_conventional`,
+/// becomes the scope again.
+pub fn to_conventional(message: &CommitMessage) -> String {
+    let rendered = message.to_string();
+    let rest = &rendered[rendered.find('\n').unwrap_or(rendered.len())..];
+    let breaking = if message.commit_type == CommitType::Breaking { "!" } else { "" };
+    let kind = kind(message.commit_type);
+
+    match message.subject.split_once(": ") {
+        Some((scope, description)) if !scope.is_empty() && !scope.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') => {
+            format!("{}({}){}: {}{}", kind, scope, breaking, description, rest)
+        }
+        _ => format!("{}{}: {}{}", kind, breaking, message.subject, rest),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, from_conventional, to_conventional, Header};
+    use {BumpLevel, CommitMessage, CommitType};
+
+    #[test]
+    fn it_parses_headers() {
+        assert_eq!(Header::parse("feat(parser)!: add trailers\n\nBody"), Some(Header { kind: "feat", scope: Some("parser"), breaking: true, description: "add trailers" }));
+        assert_eq!(Header::parse("fix: crash"), Some(Header { kind: "fix", scope: None, breaking: false, description: "crash" }));
+        assert_eq!(Header::parse("fix(): crash"), None);
+        assert_eq!(Header::parse("fix:crash"), None);
+        assert_eq!(Header::parse("fix: "), None);
+        assert_eq!(Header::parse("🐛 Fix crash"), None);
+    }
+
+    #[test]
+    fn it_classifies_both_styles() {
+        assert_eq!(classify("🐛 Fix crash"), Some((CommitType::Bugfix, "Fix crash")));
+        assert_eq!(classify("fix: crash"), Some((CommitType::Bugfix, "crash")));
+        assert_eq!(classify("feat: parser"), Some((CommitType::Feature, "parser")));
+        assert_eq!(classify("feat!: new parser"), Some((CommitType::Breaking, "new parser")));
+        assert_eq!(classify("fix: parser\n\nBREAKING CHANGE: output differs"), Some((CommitType::Bugfix, "parser")));
+        assert_eq!(classify("🐛 Fix parser\n\nBreaking-Change: output differs"), Some((CommitType::Bugfix, "Fix parser")));
+        assert_eq!(classify("perf(parser): cache tokens"), Some((CommitType::Other, "cache tokens")));
+        assert_eq!(classify("docs: update readme"), Some((CommitType::Meta, "update readme")));
+        assert_eq!(classify("wip: stuff"), None);
+        assert_eq!(classify("Update readme"), None);
+    }
+
+    #[test]
+    fn it_computes_one_bump_level_for_both_styles() {
+        let messages = ["🐛 Fix crash", "feat: add parser", "chore: release"];
+
+        assert_eq!(BumpLevel::from_commits(messages.iter().filter_map(|message| classify(message)).map(|(commit_type, _)| commit_type)), BumpLevel::Minor);
+    }
+
+    #[test]
+    fn it_translates_from_conventional_commits() {
+        assert_eq!(from_conventional("feat(parser): add trailers\n\nBody\n\nRefs: #1\n").unwrap().to_string(), "🎉 parser: add trailers\n\nBody\n\nRefs: #1\n");
+        assert_eq!(from_conventional("fix!: drop option").unwrap().to_string(), "💥 drop option");
+
+        let message = from_conventional("fix: parser\n\nBREAKING-CHANGE: output differs").unwrap();
+
+        assert_eq!(message.to_string(), "🐛 parser\n\nBREAKING-CHANGE: output differs");
+        assert_eq!(message.bump_level(), BumpLevel::Major);
+        assert_eq!(from_conventional("🐛 Fix crash"), None);
+    }
+
+    #[test]
+    fn it_translates_to_conventional_commits() {
+        let message = CommitMessage::parse("💥 Drop old parser\n\nBody\n").unwrap();
+
+        assert_eq!(to_conventional(&message), "feat!: Drop old parser\n\nBody\n");
+        assert_eq!(to_conventional(&CommitMessage::new(CommitType::Other, "Speed up parser")), "refactor: Speed up parser\n");
+        assert_eq!(to_conventional(&CommitMessage::new(CommitType::Meta, "Update readme")), "chore: Update readme\n");
+        assert_eq!(to_conventional(&CommitMessage::new(CommitType::Bugfix, "parser: fix crash")), "fix(parser): fix crash\n");
+        assert_eq!(to_conventional(&CommitMessage::new(CommitType::Breaking, "parser: drop option")), "feat(parser)!: drop option\n");
+        assert_eq!(to_conventional(&CommitMessage::new(CommitType::Other, "Note: speed up parser")), "refactor(Note): speed up parser\n");
+        assert_eq!(to_conventional(&CommitMessage::new(CommitType::Other, "Speed up: parser")), "refactor: Speed up: parser\n");
+    }
+
+    #[test]
+    fn it_round_trips_conventional_commits() {
+        let messages = ["feat(parser): add trailers\n\nBody\n\nRefs: #1\n", "feat(cli)!: drop option", "fix: crash\n\nBREAKING CHANGE: output differs", "chore(deps): bump serde"];
+
+        for message in messages.iter() {
+            assert_eq!(to_conventional(&from_conventional(message).unwrap()), *message);
+        }
+    }
+}
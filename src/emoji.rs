@@ -0,0 +1,166 @@
+use CommitType;
+
+const ZERO_WIDTH_JOINER: char = '\u{200d}';
+
+/// Check if a character starts an emoji
+fn is_pictographic(c: char) -> bool {
+    matches!(c as u32,
+        0x00a9 | 0x00ae | 0x203c | 0x2049 | 0x2122 | 0x2139 | 0x3030 | 0x303d | 0x3297 | 0x3299 |
+        0x2194..=0x21aa | 0x2300..=0x23ff | 0x25aa..=0x27bf | 0x2934..=0x2935 | 0x2b05..=0x2b55 |
+        0x1f000..=0x1faff
+    )
+}
+
+/// Check if a character modifies the emoji in front of it
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c as u32, 0xfe0e | 0xfe0f | 0x20e3 | 0x1f3fb..=0x1f3ff | 0xe0020..=0xe007f)
+}
+
+/// Return the length in bytes of the emoji at the start of the input, including modifiers and joined emoji
+pub(crate) fn leading_emoji_len(input: &str) -> Option<usize> {
+    let first = input.chars().next()?;
+
+    if !is_pictographic(first) {
+        return None;
+    }
+
+    let mut end = first.len_utf8();
+    let mut joined = false;
+
+    for (index, c) in input[end..].char_indices() {
+        if (joined && is_pictographic(c)) || is_emoji_modifier(c) || c == ZERO_WIDTH_JOINER {
+            joined = c == ZERO_WIDTH_JOINER;
+            end = first.len_utf8() + index + c.len_utf8();
+        } else {
+            break;
+        }
+    }
+
+    Some(end)
+}
+
+/// Return the length in bytes of the `:shortcode:` at the start of the input
+pub(crate) fn leading_shortcode_len(input: &str) -> Option<usize> {
+    let rest = input.strip_prefix(':')?;
+    let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '+'))?;
+
+    if end > 0 && rest[end..].starts_with(':') {
+        Some(end + 2)
+    } else {
+        None
+    }
+}
+
+/// Match the commit type emoji or shortcode at the start of the input
+///
+/// Variation selectors, skin tone modifiers and zero width joiners are ignored, so "🐛\u{fe0f}" is
+/// recognized as a bugfix. Returns the commit type and the length in bytes of the emoji as written.
+pub(crate) fn match_prefix(input: &str) -> Option<(CommitType, usize)> {
+    if let Some(len) = leading_shortcode_len(input) {
+        return CommitType::from_shortcode(&input[..len]).map(|commit_type| (commit_type, len));
+    }
+
+    let len = leading_emoji_len(input)?;
+    let base = base(&input[..len]);
+
+    CommitType::iter_variants().find(|commit_type| commit_type.emoji() == base).map(|commit_type| (commit_type, len))
+}
+
+/// Return the emoji without variation selectors, skin tone modifiers and zero width joiners
+pub(crate) fn base(emoji: &str) -> String {
+    emoji.chars().filter(|&c| !is_emoji_modifier(c) && c != ZERO_WIDTH_JOINER).collect()
+}
+
+/// Replace the shortcodes of all commit types in the input with their emoji
+///
+/// Shortcodes that don't belong to a commit type, such as `:rocket:`, are left as is.
+pub fn expand_shortcodes(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find(':') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let known = leading_shortcode_len(rest).and_then(|len| {
+            CommitType::from_shortcode(&rest[..len]).map(|commit_type| (commit_type, len))
+        });
+
+        match known {
+            Some((commit_type, len)) => {
+                output.push_str(commit_type.emoji());
+                rest = &rest[len..];
+            }
+            None => {
+                output.push(':');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_shortcodes, leading_emoji_len, leading_shortcode_len, match_prefix};
+    use CommitType;
+
+    #[test]
+    fn it_measures_emoji() {
+        assert_eq!(leading_emoji_len("🐛 Fix"), Some(4));
+        assert_eq!(leading_emoji_len("🐛\u{fe0f} Fix"), Some(7));
+        assert_eq!(leading_emoji_len("👍🏽"), Some(8));
+        assert_eq!(leading_emoji_len("👩‍🚀"), Some(11));
+        assert_eq!(leading_emoji_len("Fix"), None);
+        assert_eq!(leading_emoji_len(""), None);
+    }
+
+    #[test]
+    fn it_matches_plain_emoji() {
+        assert_eq!(match_prefix("💥 Drop"), Some((CommitType::Breaking, 4)));
+        assert_eq!(match_prefix("🌹"), Some((CommitType::Meta, 4)));
+        assert_eq!(match_prefix("🚀 Launch"), None);
+        assert_eq!(match_prefix("Launch"), None);
+    }
+
+    #[test]
+    fn it_ignores_variation_selectors() {
+        assert_eq!(match_prefix("🎉\u{fe0f} Add"), Some((CommitType::Feature, 7)));
+        assert_eq!(match_prefix("🔥\u{fe0e}\u{fe0f}"), Some((CommitType::Other, 10)));
+    }
+
+    #[test]
+    fn it_measures_shortcodes() {
+        assert_eq!(leading_shortcode_len(":bug: Fix"), Some(5));
+        assert_eq!(leading_shortcode_len(":+1:"), Some(4));
+        assert_eq!(leading_shortcode_len("::"), None);
+        assert_eq!(leading_shortcode_len(":not closed"), None);
+        assert_eq!(leading_shortcode_len("bug:"), None);
+    }
+
+    #[test]
+    fn it_matches_shortcodes() {
+        assert_eq!(match_prefix(":boom: Drop"), Some((CommitType::Breaking, 6)));
+        assert_eq!(match_prefix(":collision:"), Some((CommitType::Breaking, 11)));
+        assert_eq!(match_prefix(":bug:"), Some((CommitType::Bugfix, 5)));
+        assert_eq!(match_prefix(":rocket: Launch"), None);
+    }
+
+    #[test]
+    fn it_expands_shortcodes() {
+        assert_eq!(expand_shortcodes(":bug: Fix :tada: parsing"), "🐛 Fix 🎉 parsing");
+        assert_eq!(expand_shortcodes(":rocket::fire: at 12:30:"), ":rocket:🔥 at 12:30:");
+        assert_eq!(expand_shortcodes("no shortcodes"), "no shortcodes");
+    }
+
+    #[test]
+    fn it_ignores_skin_tones_and_joiners() {
+        assert_eq!(match_prefix("🐛🏻 Fix"), Some((CommitType::Bugfix, 8)));
+        assert_eq!(match_prefix("🌹\u{200d}"), Some((CommitType::Meta, 7)));
+        assert_eq!(match_prefix("🌹\u{200d} Fix"), Some((CommitType::Meta, 7)));
+        assert_eq!(leading_emoji_len("🌹\u{200d}Fix"), Some(7));
+        assert_eq!(match_prefix("🔥\u{200d}🚒"), None);
+    }
+}
@@ -0,0 +1,149 @@
+use std::error;
+use std::fmt;
+
+/// The error returned when parsing a `CommitType` or a `BumpLevel` from a string fails
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseVariantError {
+    kind: &'static str,
+    input: String,
+    choices: Vec<&'static str>,
+}
+
+impl ParseVariantError {
+    pub(crate) fn new(kind: &'static str, input: &str, choices: Vec<&'static str>) -> ParseVariantError {
+        ParseVariantError { kind, input: String::from(input), choices }
+    }
+
+    /// Return the input that could not be parsed
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Return the strings that would have been accepted
+    pub fn choices(&self) -> &[&'static str] {
+        &self.choices
+    }
+}
+
+impl fmt::Display for ParseVariantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown {} \"{}\", expected one of: {}", self.kind, self.input, self.choices.join(", "))
+    }
+}
+
+impl error::Error for ParseVariantError {}
+
+/// A range of byte offsets into a parsed input
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// Create a new span from a start and an end offset
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+}
+
+/// The error returned when parsing a commit message fails
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ParseError {
+    /// The message doesn't start with an emoji
+    MissingEmoji(Span),
+    /// The message starts with an emoji that isn't a commit type
+    UnknownEmoji(Span),
+    /// There is no text after the emoji
+    EmptySubject(Span),
+    /// The subject is longer than the allowed number of characters, the span covers the excess
+    SubjectTooLong { span: Span, max: usize },
+    /// A line in an almost complete trailer block isn't a `Key: value` trailer
+    MalformedTrailer(Span),
+}
+
+impl ParseError {
+    /// Return the part of the input that caused this error
+    pub fn span(&self) -> Span {
+        match *self {
+            ParseError::MissingEmoji(span) => span,
+            ParseError::UnknownEmoji(span) => span,
+            ParseError::EmptySubject(span) => span,
+            ParseError::SubjectTooLong { span, .. } => span,
+            ParseError::MalformedTrailer(span) => span,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::MissingEmoji(_) => f.write_str("message doesn't start with a commit type emoji"),
+            ParseError::UnknownEmoji(_) => f.write_str("unknown commit type emoji"),
+            ParseError::EmptySubject(_) => f.write_str("subject is empty"),
+            ParseError::SubjectTooLong { max, .. } => write!(f, "subject is longer than {} characters", max),
+            ParseError::MalformedTrailer(_) => f.write_str("malformed trailer, expected \"Key: value\""),
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// The error returned when parsing a `Version` fails
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseVersionError {
+    input: String,
+}
+
+impl ParseVersionError {
+    pub(crate) fn new(input: &str) -> ParseVersionError {
+        ParseVersionError { input: String::from(input) }
+    }
+
+    /// Return the input that could not be parsed
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+impl fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid version \"{}\", expected major.minor.patch", self.input)
+    }
+}
+
+impl error::Error for ParseVersionError {}
+
+/// The error returned when reading a configuration file fails
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ConfigError {
+    line: Option<usize>,
+    message: String,
+}
+
+impl ConfigError {
+    pub(crate) fn new<S: Into<String>>(line: Option<usize>, message: S) -> ConfigError {
+        ConfigError { line, message: message.into() }
+    }
+
+    /// Return the line the error was found on, starting at one
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Return a description of the error
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl error::Error for ConfigError {}
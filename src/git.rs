@@ -0,0 +1,232 @@
+//! Reading typed commits from the history of a local git repository
+
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+use conventional;
+use gitmoji;
+use {BumpLevel, CommitType, CommitTypeSet, TypeDefinition};
+
+/// A commit with a commit type
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Commit {
+    pub id: String,
+    /// The type of the commit, as defined in the `CommitTypeSet` it was read with
+    pub definition: TypeDefinition,
+    pub subject: String,
+    pub message: String,
+}
+
+impl Commit {
+    /// Return the built-in commit type of this commit, `None` for types added in the configuration
+    pub fn commit_type(&self) -> Option<CommitType> {
+        self.definition.commit_type()
+    }
+
+    /// Check if this commit is a breaking change, either by its type or by a `BREAKING CHANGE` trailer
+    pub fn is_breaking_change(&self) -> bool {
+        self.definition.is_breaking_change(&self.message)
+    }
+
+    /// Return the bump level required by this commit, breaking change trailers always require a major bump
+    pub fn bump_level(&self) -> BumpLevel {
+        self.definition.bump_level_of(&self.message)
+    }
+}
+
+/// The part of the history to read
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Revisions {
+    /// Every commit reachable from `HEAD`
+    All,
+    /// Commits reachable from the second revision, but not from the first
+    Between(String, String),
+    /// Commits reachable from `HEAD` since the most recent tag
+    SinceLastTag,
+}
+
+/// Run git in the given repository and return its output
+pub(crate) fn git<P: AsRef<Path>>(repo: P, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").arg("-C").arg(repo.as_ref()).args(args).output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        return Err(io::Error::other(format!("git {} failed: {}", args[0], stderr.trim())));
+    }
+
+    String::from_utf8(output.stdout).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Return the most recent tag reachable from `HEAD`
+pub fn last_tag<P: AsRef<Path>>(repo: P) -> io::Result<Option<String>> {
+    let tags = git(&repo, &["tag", "--merged", "HEAD"])?;
+
+    if tags.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let tag = git(&repo, &["describe", "--tags", "--abbrev=0", "HEAD"])?;
+
+    Ok(Some(String::from(tag.trim())))
+}
+
+/// Read the commits in the given revisions, newest first, with the built-in commit types
+pub fn log<P: AsRef<Path>>(repo: P, revisions: &Revisions) -> io::Result<Vec<Commit>> {
+    log_with(repo, revisions, &CommitTypeSet::default())
+}
+
+/// Return the type of a commit message and its subject
+fn classify<'a>(message: &'a str, types: &CommitTypeSet) -> Option<(TypeDefinition, &'a str)> {
+    if let Some((definition, subject)) = types.classify(message) {
+        return Some((definition.clone(), subject));
+    }
+
+    let (commit_type, subject) = conventional::classify(message).or_else(|| gitmoji::classify(message))?;
+
+    types.get(commit_type.name()).map(|definition| (definition.clone(), subject))
+}
+
+/// Read the commits in the given revisions, newest first
+///
+/// Commits are classified by the emoji of the types in the set, as Conventional Commits, or by their
+/// gitmoji. Commits in none of these styles, such as merge commits, are skipped.
+pub fn log_with<P: AsRef<Path>>(repo: P, revisions: &Revisions, types: &CommitTypeSet) -> io::Result<Vec<Commit>> {
+    let range = match *revisions {
+        Revisions::All => String::from("HEAD"),
+        Revisions::Between(ref from, ref to) => format!("{}..{}", from, to),
+        Revisions::SinceLastTag => match last_tag(&repo)? {
+            Some(tag) => format!("{}..HEAD", tag),
+            None => String::from("HEAD"),
+        },
+    };
+
+    let output = git(&repo, &["log", "--format=%H%x00%B%x1e", &range, "--"])?;
+
+    Ok(output.split('\x1e').filter_map(|record| {
+        let record = record.trim_start_matches('\n');
+        let separator = record.find('\0')?;
+        let (id, message) = (&record[..separator], &record[separator + 1..]);
+        let (definition, subject) = classify(message, types)?;
+
+        Some(Commit {
+            id: String::from(id),
+            definition,
+            subject: String::from(subject),
+            message: String::from(message),
+        })
+    }).collect())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    use super::{last_tag, log, log_with, Commit, Revisions};
+    use {CommitType, CommitTypeSet};
+
+    /// Create an empty repository in a fresh temporary directory
+    pub fn repo(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("emoji-commit-type-{}-{}", name, std::process::id()));
+
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        run(&path, &["init", "-q"]);
+
+        path
+    }
+
+    /// Run a git command in the repository, panicking when it fails
+    pub fn run(repo: &PathBuf, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C").arg(repo)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com", "-c", "commit.gpgsign=false", "-c", "tag.gpgsign=false"])
+            .args(args)
+            .status()
+            .unwrap();
+
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// Create an empty commit with the given message
+    pub fn commit(repo: &PathBuf, message: &str) {
+        run(repo, &["commit", "-q", "--allow-empty", "-m", message]);
+    }
+
+    #[test]
+    fn it_reads_typed_commits() {
+        let path = repo("log");
+
+        commit(&path, "🎉 Add parser");
+        commit(&path, "Untyped commit");
+        commit(&path, "refactor: clean up parser");
+        commit(&path, "📝 Update docs");
+        commit(&path, "🐛\u{fe0f} Fix parser\n\nWith a body");
+
+        let commits = log(&path, &Revisions::All).unwrap();
+
+        assert_eq!(commits.len(), 4);
+        assert_eq!(commits[0].commit_type(), Some(CommitType::Bugfix));
+        assert_eq!(commits[0].subject, "Fix parser");
+        assert_eq!(commits[0].message, "🐛\u{fe0f} Fix parser\n\nWith a body\n");
+        assert_eq!(commits[0].id.len(), 40);
+        assert_eq!(commits[1].commit_type(), Some(CommitType::Meta));
+        assert_eq!(commits[1].subject, "Update docs");
+        assert_eq!(commits[2].commit_type(), Some(CommitType::Other));
+        assert_eq!(commits[2].subject, "clean up parser");
+        assert_eq!(commits[3].commit_type(), Some(CommitType::Feature));
+        assert_eq!(commits[3].subject, "Add parser");
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn it_reads_configured_types() {
+        let path = repo("log-types");
+        let types = CommitTypeSet::parse("[[type]]\nname = \"Security\"\nemoji = \"🔒\"\nbump = \"Patch\"\n").unwrap();
+
+        commit(&path, "🎉 Add parser");
+        commit(&path, "🔒 Escape input");
+
+        let commits = log_with(&path, &Revisions::All, &types).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].definition.name, "Security");
+        assert_eq!(commits[0].commit_type(), None);
+        assert_eq!(commits[0].subject, "Escape input");
+        assert_eq!(commits[1].commit_type(), Some(CommitType::Feature));
+
+        let commits = log_with(&path, &Revisions::All, &CommitTypeSet::parse("builtin = false\n").unwrap()).unwrap();
+
+        assert_eq!(commits, vec![]);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn it_reads_commits_since_the_last_tag() {
+        let path = repo("since-tag");
+
+        assert!(last_tag(&path).is_err());
+
+        commit(&path, "🎉 Add parser");
+        assert_eq!(last_tag(&path).unwrap(), None);
+        assert_eq!(log(&path, &Revisions::SinceLastTag).unwrap().len(), 1);
+
+        run(&path, &["tag", "v1.0.0"]);
+        commit(&path, "💥 Drop old parser");
+        commit(&path, "🌹 Update readme");
+
+        let commits = log(&path, &Revisions::SinceLastTag).unwrap();
+
+        assert_eq!(last_tag(&path).unwrap(), Some(String::from("v1.0.0")));
+        assert_eq!(commits.iter().map(Commit::commit_type).collect::<Vec<_>>(), vec![Some(CommitType::Meta), Some(CommitType::Breaking)]);
+        assert_eq!(log(&path, &Revisions::Between(String::from("v1.0.0"), String::from("HEAD~1"))).unwrap().len(), 1);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
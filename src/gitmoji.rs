@@ -0,0 +1,166 @@
+//! Classifying commits that use the gitmoji set of emoji
+//!
+//! See <https://gitmoji.dev> for the full list and the intended use of each emoji.
+
+use emoji::{base, leading_emoji_len, leading_shortcode_len};
+use CommitType;
+
+/// A gitmoji and the commit type it maps onto
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Gitmoji {
+    pub emoji: &'static str,
+    pub code: &'static str,
+    pub description: &'static str,
+    pub commit_type: CommitType,
+}
+
+/// All gitmoji, in the order of the official list
+pub const GITMOJIS: &[Gitmoji] = &[
+    Gitmoji { emoji: "🎨", code: ":art:", description: "Improve structure / format of the code", commit_type: CommitType::Other },
+    Gitmoji { emoji: "⚡\u{fe0f}", code: ":zap:", description: "Improve performance", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🔥", code: ":fire:", description: "Remove code or files", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🐛", code: ":bug:", description: "Fix a bug", commit_type: CommitType::Bugfix },
+    Gitmoji { emoji: "🚑\u{fe0f}", code: ":ambulance:", description: "Critical hotfix", commit_type: CommitType::Bugfix },
+    Gitmoji { emoji: "✨", code: ":sparkles:", description: "Introduce new features", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "📝", code: ":memo:", description: "Add or update documentation", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🚀", code: ":rocket:", description: "Deploy stuff", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "💄", code: ":lipstick:", description: "Add or update the UI and style files", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "🎉", code: ":tada:", description: "Begin a project", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "✅", code: ":white_check_mark:", description: "Add, update, or pass tests", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🔒\u{fe0f}", code: ":lock:", description: "Fix security or privacy issues", commit_type: CommitType::Bugfix },
+    Gitmoji { emoji: "🔐", code: ":closed_lock_with_key:", description: "Add or update secrets", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🔖", code: ":bookmark:", description: "Release / Version tags", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🚨", code: ":rotating_light:", description: "Fix compiler / linter warnings", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🚧", code: ":construction:", description: "Work in progress", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "💚", code: ":green_heart:", description: "Fix CI Build", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "⬇\u{fe0f}", code: ":arrow_down:", description: "Downgrade dependencies", commit_type: CommitType::Other },
+    Gitmoji { emoji: "⬆\u{fe0f}", code: ":arrow_up:", description: "Upgrade dependencies", commit_type: CommitType::Other },
+    Gitmoji { emoji: "📌", code: ":pushpin:", description: "Pin dependencies to specific versions", commit_type: CommitType::Other },
+    Gitmoji { emoji: "👷", code: ":construction_worker:", description: "Add or update CI build system", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "📈", code: ":chart_with_upwards_trend:", description: "Add or update analytics or track code", commit_type: CommitType::Other },
+    Gitmoji { emoji: "♻\u{fe0f}", code: ":recycle:", description: "Refactor code", commit_type: CommitType::Other },
+    Gitmoji { emoji: "➕", code: ":heavy_plus_sign:", description: "Add a dependency", commit_type: CommitType::Other },
+    Gitmoji { emoji: "➖", code: ":heavy_minus_sign:", description: "Remove a dependency", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🔧", code: ":wrench:", description: "Add or update configuration files", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🔨", code: ":hammer:", description: "Add or update development scripts", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🌐", code: ":globe_with_meridians:", description: "Internationalization and localization", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "✏\u{fe0f}", code: ":pencil2:", description: "Fix typos", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "💩", code: ":poop:", description: "Write bad code that needs to be improved", commit_type: CommitType::Other },
+    Gitmoji { emoji: "⏪\u{fe0f}", code: ":rewind:", description: "Revert changes", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🔀", code: ":twisted_rightwards_arrows:", description: "Merge branches", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "📦\u{fe0f}", code: ":package:", description: "Add or update compiled files or packages", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "👽\u{fe0f}", code: ":alien:", description: "Update code due to external API changes", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🚚", code: ":truck:", description: "Move or rename resources (e.g.: files, paths, routes)", commit_type: CommitType::Other },
+    Gitmoji { emoji: "📄", code: ":page_facing_up:", description: "Add or update license", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "💥", code: ":boom:", description: "Introduce breaking changes", commit_type: CommitType::Breaking },
+    Gitmoji { emoji: "🍱", code: ":bento:", description: "Add or update assets", commit_type: CommitType::Other },
+    Gitmoji { emoji: "♿\u{fe0f}", code: ":wheelchair:", description: "Improve accessibility", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "💡", code: ":bulb:", description: "Add or update comments in source code", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🍻", code: ":beers:", description: "Write code drunkenly", commit_type: CommitType::Other },
+    Gitmoji { emoji: "💬", code: ":speech_balloon:", description: "Add or update text and literals", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🗃\u{fe0f}", code: ":card_file_box:", description: "Perform database related changes", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🔊", code: ":loud_sound:", description: "Add or update logs", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🔇", code: ":mute:", description: "Remove logs", commit_type: CommitType::Other },
+    Gitmoji { emoji: "👥", code: ":busts_in_silhouette:", description: "Add or update contributor(s)", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🚸", code: ":children_crossing:", description: "Improve user experience / usability", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "🏗\u{fe0f}", code: ":building_construction:", description: "Make architectural changes", commit_type: CommitType::Other },
+    Gitmoji { emoji: "📱", code: ":iphone:", description: "Work on responsive design", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "🤡", code: ":clown_face:", description: "Mock things", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🥚", code: ":egg:", description: "Add or update an easter egg", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "🙈", code: ":see_no_evil:", description: "Add or update a .gitignore file", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "📸", code: ":camera_flash:", description: "Add or update snapshots", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "⚗\u{fe0f}", code: ":alembic:", description: "Perform experiments", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🔍\u{fe0f}", code: ":mag:", description: "Improve SEO", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🏷\u{fe0f}", code: ":label:", description: "Add or update types", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🌱", code: ":seedling:", description: "Add or update seed files", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🚩", code: ":triangular_flag_on_post:", description: "Add, update, or remove feature flags", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "🥅", code: ":goal_net:", description: "Catch errors", commit_type: CommitType::Bugfix },
+    Gitmoji { emoji: "💫", code: ":dizzy:", description: "Add or update animations and transitions", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "🗑\u{fe0f}", code: ":wastebasket:", description: "Deprecate code that needs to be cleaned up", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🛂", code: ":passport_control:", description: "Work on code related to authorization, roles and permissions", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "🩹", code: ":adhesive_bandage:", description: "Simple fix for a non-critical issue", commit_type: CommitType::Bugfix },
+    Gitmoji { emoji: "🧐", code: ":monocle_face:", description: "Data exploration/inspection", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "⚰\u{fe0f}", code: ":coffin:", description: "Remove dead code", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🧪", code: ":test_tube:", description: "Add a failing test", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "👔", code: ":necktie:", description: "Add or update business logic", commit_type: CommitType::Feature },
+    Gitmoji { emoji: "🩺", code: ":stethoscope:", description: "Add or update healthcheck", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🧱", code: ":bricks:", description: "Infrastructure related changes", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🧑\u{200d}💻", code: ":technologist:", description: "Improve developer experience", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "💸", code: ":money_with_wings:", description: "Add sponsorships or money related infrastructure", commit_type: CommitType::Meta },
+    Gitmoji { emoji: "🧵", code: ":thread:", description: "Add or update code related to multithreading or concurrency", commit_type: CommitType::Other },
+    Gitmoji { emoji: "🦺", code: ":safety_vest:", description: "Add or update code related to validation", commit_type: CommitType::Other },
+];
+
+/// Return the gitmoji for the given emoji or shortcode, ignoring variation selectors
+pub fn find(emoji: &str) -> Option<&'static Gitmoji> {
+    let emoji = emoji.trim();
+    let emoji_base = base(emoji);
+
+    GITMOJIS.iter().find(|gitmoji| gitmoji.code == emoji || base(gitmoji.emoji) == emoji_base)
+}
+
+/// Parse the subject line of a commit message that starts with a commit type emoji or a gitmoji
+///
+/// Returns the commit type and the remaining subject.
+pub fn classify(message: &str) -> Option<(CommitType, &str)> {
+    if let Some(found) = CommitType::from_message(message) {
+        return Some(found);
+    }
+
+    let subject = message.lines().next().unwrap_or("").trim_start();
+    let len = leading_shortcode_len(subject).or_else(|| leading_emoji_len(subject))?;
+
+    find(&subject[..len]).map(|gitmoji| (gitmoji.commit_type, subject[len..].trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, find, GITMOJIS};
+    use {BumpLevel, CommitType};
+
+    #[test]
+    fn it_has_unique_gitmoji() {
+        for (index, gitmoji) in GITMOJIS.iter().enumerate() {
+            assert_eq!(find(gitmoji.emoji), Some(gitmoji), "{}", gitmoji.code);
+            assert_eq!(find(gitmoji.code), Some(gitmoji), "{}", gitmoji.code);
+            assert!(GITMOJIS[index + 1..].iter().all(|other| other.code != gitmoji.code));
+        }
+    }
+
+    #[test]
+    fn it_agrees_with_the_commit_types() {
+        for commit_type in CommitType::iter_variants() {
+            if let Some(gitmoji) = find(commit_type.emoji()) {
+                assert_eq!(gitmoji.commit_type, commit_type);
+            }
+        }
+    }
+
+    #[test]
+    fn it_finds_gitmoji_with_and_without_variation_selectors() {
+        assert_eq!(find("\u{267b}").unwrap().code, ":recycle:");
+        assert_eq!(find("\u{267b}\u{fe0f}").unwrap().code, ":recycle:");
+        assert_eq!(find("⚡").unwrap().commit_type, CommitType::Other);
+        assert_eq!(find("🧑\u{200d}💻").unwrap().code, ":technologist:");
+        assert_eq!(find("🌹"), None);
+    }
+
+    #[test]
+    fn it_classifies_gitmoji_messages() {
+        assert_eq!(classify("✨ Add parser"), Some((CommitType::Feature, "Add parser")));
+        assert_eq!(classify("🚑\u{fe0f} Fix crash on start"), Some((CommitType::Bugfix, "Fix crash on start")));
+        assert_eq!(classify(":memo: Update readme"), Some((CommitType::Meta, "Update readme")));
+        assert_eq!(classify("⬆️ Bump dependencies"), Some((CommitType::Other, "Bump dependencies")));
+        assert_eq!(classify("🌹 Update readme"), Some((CommitType::Meta, "Update readme")));
+        assert_eq!(classify("🦄 Magic"), None);
+        assert_eq!(classify("Update readme"), None);
+    }
+
+    #[test]
+    fn it_bumps_gitmoji_commits() {
+        let messages = ["📝 Update docs", "🐛 Fix parser", "✨ Add formatter"];
+        let levels = messages.iter().filter_map(|message| classify(message)).map(|(commit_type, _)| commit_type.bump_level());
+
+        assert_eq!(levels.max(), Some(BumpLevel::Minor));
+    }
+}
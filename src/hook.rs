@@ -0,0 +1,178 @@
+//! Git hooks that enforce emoji commit messages
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use config::Config;
+use git;
+use lint::{self, Violation};
+use CommitTypeSet;
+
+/// The line that marks a hook as installed by this crate
+const MARKER: &str = "# Installed by emoji-commit-type";
+
+/// The line below which git ignores everything when committing with `--verbose`
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// The subjects that git writes itself, for merges, reverts and `git commit --fixup`
+const GENERATED_PREFIXES: &[&str] = &[
+    "Merge branch ", "Merge branches ", "Merge remote-tracking branch ", "Merge tag ", "Merge commit ", "Merge pull request ",
+    "Revert \"", "fixup! ", "squash! ", "amend! ",
+];
+
+/// Remove the comment lines that git adds to the commit message file
+pub fn strip_comments(message: &str) -> String {
+    message.split_inclusive('\n')
+        .take_while(|line| line.trim_end() != SCISSORS)
+        .filter(|line| !line.starts_with('#'))
+        .collect()
+}
+
+/// Repair a commit message file as written by git, as done by the `commit-msg` hook, see `lint::fix`
+///
+/// Only the message itself is repaired. Comment lines, and the diff below the scissors line when
+/// committing with `--verbose`, are kept as they are.
+pub fn fix(message: &str, config: &Config) -> String {
+    let is_comment = |line: &&str| line.starts_with('#');
+    let start: usize = message.split_inclusive('\n').take_while(is_comment).map(str::len).sum();
+    let end = start + message[start..].split_inclusive('\n').take_while(|line| !is_comment(line)).map(str::len).sum::<usize>();
+
+    format!("{}{}{}", &message[..start], lint::fix(&message[start..end], config), &message[end..])
+}
+
+/// Check if a message was written by git rather than by hand, e.g. for a merge or a revert
+///
+/// These messages don't start with a commit type emoji, and are let through by the hooks.
+pub fn is_generated(message: &str) -> bool {
+    GENERATED_PREFIXES.iter().any(|prefix| message.starts_with(prefix))
+}
+
+/// Lint a commit message file as written by git, as done by the `commit-msg` hook
+pub fn lint(message: &str, config: &Config) -> Vec<Violation> {
+    let message = strip_comments(message);
+
+    if is_generated(&message) {
+        return Vec::new();
+    }
+
+    lint::lint(&message, config)
+}
+
+/// Return a list of the commit types in the set, one per line with their description
+pub fn valid_types(types: &CommitTypeSet) -> String {
+    types.iter().map(|definition| format!("{}  {}\n", definition.emoji, definition.description)).collect()
+}
+
+/// Install a hook into the repository that runs `emoji-commit-type hook <name>`
+///
+/// An existing hook is only replaced if it was installed by this crate. Returns the path of the hook.
+pub fn install<P: AsRef<Path>>(repo: P, name: &str) -> io::Result<PathBuf> {
+    let hooks = git::git(&repo, &["rev-parse", "--git-path", "hooks"])?;
+    let path = repo.as_ref().join(hooks.trim()).join(name);
+
+    if let Ok(existing) = fs::read_to_string(&path) {
+        if !existing.contains(MARKER) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", path.display())));
+        }
+    }
+
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(&path, format!("#!/bin/sh\n{}\nexec emoji-commit-type hook {} \"$@\"\n", MARKER, name))?;
+    make_executable(&path)?;
+
+    Ok(path)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{fix, install, is_generated, lint, strip_comments, valid_types};
+    use config::Config;
+    use git::tests::repo;
+    use lint::Rule;
+    use CommitTypeSet;
+
+    #[test]
+    fn it_strips_comments() {
+        let message = "🐛 Fix\n\n# Please enter the commit message\n#\nBody\n# ------------------------ >8 ------------------------\ndiff --git a/x b/x\n";
+
+        assert_eq!(strip_comments(message), "🐛 Fix\n\nBody\n");
+    }
+
+    #[test]
+    fn it_fixes_only_the_message() {
+        let message = ":bug:  Fix the parser \nIt crashed\n\n# Please enter the commit message \n#\n# ------------------------ >8 ------------------------\n diff --git a/x b/x \n";
+
+        assert_eq!(fix(message, &Config::default()), "🐛 Fix the parser\n\nIt crashed\n\n# Please enter the commit message \n#\n# ------------------------ >8 ------------------------\n diff --git a/x b/x \n");
+        assert_eq!(fix("# Template \n:bug: Fix\n", &Config::default()), "# Template \n🐛 Fix\n");
+        assert_eq!(fix("# Only comments \n", &Config::default()), "# Only comments \n");
+    }
+
+    #[test]
+    fn it_lints_messages() {
+        assert_eq!(lint("🐛 Fix the parser\n# Comment\n", &Config::default()), vec![]);
+        assert_eq!(lint("# Comment\nAdd hook\n", &Config::default())[0].rule, Rule::LeadingEmoji);
+        assert_eq!(lint("🐛 Fixed the parser\n", &Config::default())[0].rule, Rule::ImperativeMood);
+    }
+
+    #[test]
+    fn it_lets_generated_messages_through() {
+        let messages = [
+            "Merge branch 'feature'\n",
+            "Merge branch 'feature' into main\n\n# Conflicts:\n#\tsrc/lib.rs\n",
+            "Merge remote-tracking branch 'origin/main'\n",
+            "Merge pull request #12 from user/feature\n",
+            "Revert \"🐛 Fix y\"\n\nThis reverts commit 0123456789abcdef.\n",
+            "fixup! 🐛 Fix y\n",
+            "squash! 🐛 Fix y\n",
+            "amend! 🐛 Fix y\n\n🐛 Fix y properly\n",
+        ];
+
+        for message in messages.iter() {
+            assert!(is_generated(&strip_comments(message)), "{}", message);
+            assert_eq!(lint(message, &Config::default()), vec![], "{}", message);
+        }
+
+        assert!(!is_generated("Merge the parsers\n"));
+        assert!(!is_generated("Revert the parser\n"));
+        assert_eq!(lint("Merge the parsers\n", &Config::default())[0].rule, Rule::LeadingEmoji);
+    }
+
+    #[test]
+    fn it_lists_valid_types() {
+        assert_eq!(valid_types(&CommitTypeSet::default()), "💥  Breaking change\n🎉  New functionality\n🐛  Bugfix\n🔥  Cleanup / Performance\n🌹  Meta\n");
+
+        let types = CommitTypeSet::parse("builtin = false\n\n[[type]]\nname = \"Security\"\nemoji = \"🔒\"\ndescription = \"Security fix\"\n").unwrap();
+
+        assert_eq!(valid_types(&types), "🔒  Security fix\n");
+    }
+
+    #[test]
+    fn it_installs_a_hook() {
+        let path = repo("install-hook");
+        let hook = install(&path, "commit-msg").unwrap();
+
+        assert_eq!(hook, path.join(".git/hooks/commit-msg"));
+        assert!(fs::read_to_string(&hook).unwrap().contains("exec emoji-commit-type hook commit-msg \"$@\""));
+        assert!(install(&path, "commit-msg").is_ok());
+
+        fs::write(&hook, "#!/bin/sh\nexit 0\n").unwrap();
+        assert!(install(&path, "commit-msg").is_err());
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -1,5 +1,36 @@
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
+use std::cmp::Ordering;
 use std::fmt;
 use std::mem;
+use std::str::FromStr;
+
+pub mod changelog;
+pub mod config;
+pub mod conventional;
+mod emoji;
+mod error;
+pub mod git;
+pub mod gitmoji;
+pub mod hook;
+pub mod lint;
+mod message;
+pub mod picker;
+mod registry;
+pub mod release;
+#[cfg(feature = "serde")]
+pub mod serialize;
+mod toml;
+mod version;
+
+pub use emoji::expand_shortcodes;
+pub use error::{ConfigError, ParseError, ParseVariantError, ParseVersionError, Span};
+pub use message::{CommitMessage, Trailer};
+pub use registry::{CommitTypeSet, TypeDefinition};
+pub use version::{Identifier, Version};
 
 /// A semver bump level
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
@@ -10,6 +41,8 @@ pub enum BumpLevel {
     None,
 }
 
+const BUMP_LEVELS: [BumpLevel; 4] = [BumpLevel::Major, BumpLevel::Minor, BumpLevel::Patch, BumpLevel::None];
+
 impl BumpLevel {
     /// Return the name of this bump level
     pub fn name(&self) -> &'static str {
@@ -20,6 +53,143 @@ impl BumpLevel {
             BumpLevel::None => "None",
         }
     }
+
+    /// Return the highest bump level required by any of the given commits
+    pub fn from_commits<I>(commits: I) -> BumpLevel where I: IntoIterator, I::Item: Into<BumpLevel> {
+        commits.into_iter().map(Into::into).max().unwrap_or(BumpLevel::None)
+    }
+
+    /// Return the version that follows the given version at this bump level
+    ///
+    /// While the major version is zero, `Major` bumps the minor version and `Minor` bumps the patch
+    /// version. A pre-release that already leads up to the bumped version is released as is.
+    pub fn apply(&self, version: &Version) -> Version {
+        self.bump(version, true)
+    }
+
+    pub(crate) fn bump(&self, version: &Version, initial_development: bool) -> Version {
+        let level = match (version.major, *self) {
+            (0, BumpLevel::Major) if initial_development => BumpLevel::Minor,
+            (0, BumpLevel::Minor) if initial_development => BumpLevel::Patch,
+            (_, level) => level,
+        };
+
+        let prerelease = version.is_prerelease();
+        let mut next = Version::new(version.major, version.minor, version.patch);
+
+        match level {
+            BumpLevel::Major if prerelease && version.minor == 0 && version.patch == 0 => {}
+            BumpLevel::Major => {
+                next.major += 1;
+                next.minor = 0;
+                next.patch = 0;
+            }
+            BumpLevel::Minor if prerelease && version.patch == 0 => {}
+            BumpLevel::Minor => {
+                next.minor += 1;
+                next.patch = 0;
+            }
+            BumpLevel::Patch if prerelease => {}
+            BumpLevel::Patch => next.patch += 1,
+            BumpLevel::None => return version.clone(),
+        }
+
+        next
+    }
+
+    /// Return the next pre-release version at this bump level, using `tag` as the pre-release name
+    ///
+    /// `1.2.0` becomes `2.0.0-rc.0` at the `Major` level, and any further bump that `2.0.0` already
+    /// covers gives `2.0.0-rc.1`. Use `Version::promote` to turn the last candidate into a release.
+    pub fn apply_prerelease(&self, version: &Version, tag: &str) -> Version {
+        self.bump_prerelease(version, tag, true)
+    }
+
+    pub(crate) fn bump_prerelease(&self, version: &Version, tag: &str, initial_development: bool) -> Version {
+        if *self == BumpLevel::None {
+            return version.clone();
+        }
+
+        let mut next = self.bump(version, initial_development);
+
+        let number = match version.pre.as_slice() {
+            [Identifier::AlphaNumeric(name), Identifier::Numeric(number)] if name == tag && next == version.promote() => number + 1,
+            _ => 0,
+        };
+
+        next.pre = vec![Identifier::AlphaNumeric(String::from(tag)), Identifier::Numeric(number)];
+        next
+    }
+
+    fn rank(&self) -> u8 {
+        match *self {
+            BumpLevel::Major => 3,
+            BumpLevel::Minor => 2,
+            BumpLevel::Patch => 1,
+            BumpLevel::None => 0,
+        }
+    }
+}
+
+/// Bump levels are ordered by the size of the bump, `Major` being the greatest
+impl Ord for BumpLevel {
+    fn cmp(&self, other: &BumpLevel) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for BumpLevel {
+    fn partial_cmp(&self, other: &BumpLevel) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<CommitType> for BumpLevel {
+    fn from(commit_type: CommitType) -> BumpLevel {
+        commit_type.bump_level()
+    }
+}
+
+impl<'a> From<&'a CommitType> for BumpLevel {
+    fn from(commit_type: &'a CommitType) -> BumpLevel {
+        commit_type.bump_level()
+    }
+}
+
+impl<'a> From<&'a CommitMessage> for BumpLevel {
+    fn from(message: &'a CommitMessage) -> BumpLevel {
+        message.bump_level()
+    }
+}
+
+impl fmt::Display for BumpLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for BumpLevel {
+    type Err = ParseVariantError;
+
+    /// Parse a bump level from its name, ignoring case
+    fn from_str(s: &str) -> Result<BumpLevel, ParseVariantError> {
+        let input = s.trim();
+
+        BUMP_LEVELS.iter().cloned().find(|level| level.name().eq_ignore_ascii_case(input)).ok_or_else(|| {
+            ParseVariantError::new("bump level", s, BUMP_LEVELS.iter().map(BumpLevel::name).collect())
+        })
+    }
+}
+
+/// The ways a commit type can be written as a string, e.g. when storing it
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Representation {
+    /// The emoji, e.g. "🐛"
+    Emoji,
+    /// The name of the variant, e.g. "Bugfix"
+    Name,
+    /// The description, e.g. "Cleanup / Performance"
+    Description,
 }
 
 /// A specific commit type
@@ -82,6 +252,74 @@ impl CommitType {
         }
     }
 
+    /// Return the name of this commit type
+    pub fn name(&self) -> &'static str {
+        match *self {
+            CommitType::Breaking => "Breaking",
+            CommitType::Feature => "Feature",
+            CommitType::Bugfix => "Bugfix",
+            CommitType::Other => "Other",
+            CommitType::Meta => "Meta",
+        }
+    }
+
+    /// Return the commit type for the given emoji, ignoring variation selectors and skin tones
+    pub fn from_emoji(emoji: &str) -> Option<CommitType> {
+        let emoji = emoji.trim();
+
+        match emoji::match_prefix(emoji) {
+            Some((commit_type, len)) if len == emoji.len() => Some(commit_type),
+            _ => None,
+        }
+    }
+
+    /// Parse the subject line of a commit message, returning the commit type and the remaining subject
+    pub fn from_message(message: &str) -> Option<(CommitType, &str)> {
+        let subject = message.lines().next().unwrap_or("").trim_start();
+
+        emoji::match_prefix(subject).map(|(commit_type, len)| (commit_type, subject[len..].trim()))
+    }
+
+    /// Return the shortcode for this commit type, as used by GitHub and Slack
+    pub fn shortcode(&self) -> &'static str {
+        self.shortcodes()[0]
+    }
+
+    /// Return all shortcodes for this commit type, starting with the preferred one
+    pub fn shortcodes(&self) -> &'static [&'static str] {
+        match *self {
+            CommitType::Breaking => &[":boom:", ":collision:"],
+            CommitType::Feature => &[":tada:"],
+            CommitType::Bugfix => &[":bug:"],
+            CommitType::Other => &[":fire:"],
+            CommitType::Meta => &[":rose:"],
+        }
+    }
+
+    /// Return the commit type for the given shortcode
+    pub fn from_shortcode(shortcode: &str) -> Option<CommitType> {
+        let shortcode = shortcode.trim();
+
+        CommitType::iter_variants().find(|commit_type| commit_type.shortcodes().contains(&shortcode))
+    }
+
+    /// Return this commit type written in the given representation
+    pub fn to_representation(&self, representation: Representation) -> &'static str {
+        match representation {
+            Representation::Emoji => self.emoji(),
+            Representation::Name => self.name(),
+            Representation::Description => self.description(),
+        }
+    }
+
+    /// Parse a commit type written in the given representation
+    pub fn from_representation(input: &str, representation: Representation) -> Option<CommitType> {
+        match representation {
+            Representation::Emoji => CommitType::from_emoji(input),
+            _ => CommitType::iter_variants().find(|commit_type| commit_type.to_representation(representation) == input),
+        }
+    }
+
     /// Return the bump level for this commit type
     pub fn bump_level(&self) -> BumpLevel {
         match *self {
@@ -111,6 +349,29 @@ impl fmt::Debug for CommitType {
     }
 }
 
+impl fmt::Display for CommitType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.emoji())
+    }
+}
+
+impl FromStr for CommitType {
+    type Err = ParseVariantError;
+
+    /// Parse a commit type from its emoji, or from its name ignoring case
+    fn from_str(s: &str) -> Result<CommitType, ParseVariantError> {
+        let input = s.trim();
+
+        CommitType::iter_variants().find(|commit_type| {
+            commit_type.name().eq_ignore_ascii_case(input)
+        }).or_else(|| CommitType::from_emoji(input)).ok_or_else(|| {
+            let choices = CommitType::iter_variants().flat_map(|commit_type| vec![commit_type.emoji(), commit_type.name()]).collect();
+
+            ParseVariantError::new("commit type", s, choices)
+        })
+    }
+}
+
 impl Iterator for CommitTypeIterator {
     type Item = CommitType;
 
@@ -141,7 +402,8 @@ impl ExactSizeIterator for CommitTypeIterator {
 
 #[cfg(test)]
 mod tests {
-    use super::{CommitType, BumpLevel};
+    use super::{CommitType, BumpLevel, Representation, Version};
+    use std::str::FromStr;
 
     #[test]
     fn it_gives_the_first_type() {
@@ -209,6 +471,164 @@ mod tests {
         assert_eq!(CommitType::Meta.bump_level().name(), "None");
     }
 
+    #[test]
+    fn it_gives_a_type_from_an_emoji() {
+        assert_eq!(CommitType::from_emoji("💥"), Some(CommitType::Breaking));
+        assert_eq!(CommitType::from_emoji("🌹"), Some(CommitType::Meta));
+        assert_eq!(CommitType::from_emoji("🎉\u{fe0f} "), Some(CommitType::Feature));
+        assert_eq!(CommitType::from_emoji("🎉 Add"), None);
+        assert_eq!(CommitType::from_emoji("🚀"), None);
+    }
+
+    #[test]
+    fn it_gives_a_shortcode() {
+        assert_eq!(CommitType::Breaking.shortcode(), ":boom:");
+        assert_eq!(CommitType::Feature.shortcode(), ":tada:");
+        assert_eq!(CommitType::Bugfix.shortcode(), ":bug:");
+        assert_eq!(CommitType::Other.shortcode(), ":fire:");
+        assert_eq!(CommitType::Meta.shortcode(), ":rose:");
+    }
+
+    #[test]
+    fn it_gives_a_type_from_a_shortcode() {
+        assert_eq!(CommitType::from_shortcode(":boom:"), Some(CommitType::Breaking));
+        assert_eq!(CommitType::from_shortcode(":collision:"), Some(CommitType::Breaking));
+        assert_eq!(CommitType::from_shortcode(":rose:"), Some(CommitType::Meta));
+        assert_eq!(CommitType::from_shortcode(":rocket:"), None);
+    }
+
+    #[test]
+    fn it_parses_a_message() {
+        assert_eq!(CommitType::from_message("🎉 Add a parser"), Some((CommitType::Feature, "Add a parser")));
+        assert_eq!(CommitType::from_message("🐛  Fix a crash  \n\nSome body"), Some((CommitType::Bugfix, "Fix a crash")));
+        assert_eq!(CommitType::from_message(" 🔥\u{fe0f} Remove cruft"), Some((CommitType::Other, "Remove cruft")));
+        assert_eq!(CommitType::from_message("🌹"), Some((CommitType::Meta, "")));
+        assert_eq!(CommitType::from_message(":bug: Fix a crash"), Some((CommitType::Bugfix, "Fix a crash")));
+        assert_eq!(CommitType::from_message("Add a parser"), None);
+        assert_eq!(CommitType::from_message(""), None);
+    }
+
+    #[test]
+    fn it_gives_a_name() {
+        assert_eq!(CommitType::Breaking.name(), "Breaking");
+        assert_eq!(CommitType::Feature.name(), "Feature");
+        assert_eq!(CommitType::Bugfix.name(), "Bugfix");
+        assert_eq!(CommitType::Other.name(), "Other");
+        assert_eq!(CommitType::Meta.name(), "Meta");
+    }
+
+    #[test]
+    fn it_parses_a_type_from_a_string() {
+        assert_eq!(CommitType::from_str("💥"), Ok(CommitType::Breaking));
+        assert_eq!(CommitType::from_str("Breaking"), Ok(CommitType::Breaking));
+        assert_eq!(CommitType::from_str("feature"), Ok(CommitType::Feature));
+        assert_eq!(" BUGFIX ".parse::<CommitType>(), Ok(CommitType::Bugfix));
+        assert_eq!(":tada:".parse::<CommitType>(), Ok(CommitType::Feature));
+
+        let err = CommitType::from_str("Cleanup").unwrap_err();
+
+        assert_eq!(err.input(), "Cleanup");
+        assert_eq!(err.choices().len(), 10);
+        assert_eq!(err.to_string(), "unknown commit type \"Cleanup\", expected one of: 💥, Breaking, 🎉, Feature, 🐛, Bugfix, 🔥, Other, 🌹, Meta");
+    }
+
+    #[test]
+    fn it_parses_a_bump_level_from_a_string() {
+        assert_eq!(BumpLevel::from_str("Major"), Ok(BumpLevel::Major));
+        assert_eq!(BumpLevel::from_str("minor"), Ok(BumpLevel::Minor));
+        assert_eq!(BumpLevel::from_str("PATCH"), Ok(BumpLevel::Patch));
+        assert_eq!(BumpLevel::from_str("none"), Ok(BumpLevel::None));
+        assert_eq!(BumpLevel::from_str("huge").unwrap_err().to_string(), "unknown bump level \"huge\", expected one of: Major, Minor, Patch, None");
+    }
+
+    #[test]
+    fn it_displays_types_and_bump_levels() {
+        assert_eq!(CommitType::Bugfix.to_string(), "🐛");
+        assert_eq!(BumpLevel::Minor.to_string(), "Minor");
+    }
+
+    #[test]
+    fn it_orders_bump_levels() {
+        assert!(BumpLevel::Major > BumpLevel::Minor);
+        assert!(BumpLevel::Minor > BumpLevel::Patch);
+        assert!(BumpLevel::Patch > BumpLevel::None);
+        assert_eq!(BumpLevel::Patch.max(BumpLevel::Minor), BumpLevel::Minor);
+    }
+
+    #[test]
+    fn it_gives_the_bump_level_for_many_commits() {
+        assert_eq!(BumpLevel::from_commits(vec![CommitType::Meta, CommitType::Bugfix, CommitType::Other]), BumpLevel::Patch);
+        assert_eq!(BumpLevel::from_commits([CommitType::Feature, CommitType::Breaking, CommitType::Bugfix].iter()), BumpLevel::Major);
+        assert_eq!(BumpLevel::from_commits(CommitType::iter_variants().skip(1)), BumpLevel::Minor);
+        assert_eq!(BumpLevel::from_commits(Vec::<CommitType>::new()), BumpLevel::None);
+    }
+
+    #[test]
+    fn it_applies_a_bump_level() {
+        let version = Version::parse("1.2.3+build.1").unwrap();
+
+        assert_eq!(BumpLevel::Major.apply(&version).to_string(), "2.0.0");
+        assert_eq!(BumpLevel::Minor.apply(&version).to_string(), "1.3.0");
+        assert_eq!(BumpLevel::Patch.apply(&version).to_string(), "1.2.4");
+        assert_eq!(BumpLevel::None.apply(&version).to_string(), "1.2.3+build.1");
+    }
+
+    #[test]
+    fn it_applies_a_bump_level_before_one_point_oh() {
+        let version = Version::new(0, 4, 2);
+
+        assert_eq!(BumpLevel::Major.apply(&version).to_string(), "0.5.0");
+        assert_eq!(BumpLevel::Minor.apply(&version).to_string(), "0.4.3");
+        assert_eq!(BumpLevel::Patch.apply(&version).to_string(), "0.4.3");
+    }
+
+    #[test]
+    fn it_applies_a_bump_level_to_a_prerelease() {
+        assert_eq!(BumpLevel::Major.apply(&Version::parse("2.0.0-rc.1").unwrap()).to_string(), "2.0.0");
+        assert_eq!(BumpLevel::Major.apply(&Version::parse("2.1.0-rc.1").unwrap()).to_string(), "3.0.0");
+        assert_eq!(BumpLevel::Minor.apply(&Version::parse("2.1.0-rc.1").unwrap()).to_string(), "2.1.0");
+        assert_eq!(BumpLevel::Patch.apply(&Version::parse("2.1.1-rc.1").unwrap()).to_string(), "2.1.1");
+    }
+
+    #[test]
+    fn it_applies_a_bump_level_as_a_prerelease() {
+        let version = BumpLevel::Major.apply_prerelease(&Version::new(1, 2, 0), "rc");
+        assert_eq!(version.to_string(), "2.0.0-rc.0");
+
+        let version = BumpLevel::Major.apply_prerelease(&version, "rc");
+        assert_eq!(version.to_string(), "2.0.0-rc.1");
+
+        let version = BumpLevel::Patch.apply_prerelease(&version, "rc");
+        assert_eq!(version.to_string(), "2.0.0-rc.2");
+
+        assert_eq!(version.promote().to_string(), "2.0.0");
+    }
+
+    #[test]
+    fn it_restarts_prereleases_when_the_target_changes() {
+        let version = Version::parse("2.0.1-rc.3").unwrap();
+
+        assert_eq!(BumpLevel::Minor.apply_prerelease(&version, "rc").to_string(), "2.1.0-rc.0");
+        assert_eq!(BumpLevel::Patch.apply_prerelease(&version, "beta").to_string(), "2.0.1-beta.0");
+        assert_eq!(BumpLevel::None.apply_prerelease(&version, "rc").to_string(), "2.0.1-rc.3");
+        assert_eq!(BumpLevel::Major.apply_prerelease(&Version::new(0, 3, 1), "rc").to_string(), "0.4.0-rc.0");
+    }
+
+    #[test]
+    fn it_converts_between_representations() {
+        for commit_type in CommitType::iter_variants() {
+            for representation in [Representation::Emoji, Representation::Name, Representation::Description].iter() {
+                let text = commit_type.to_representation(*representation);
+
+                assert_eq!(CommitType::from_representation(text, *representation), Some(commit_type));
+            }
+        }
+
+        assert_eq!(CommitType::Other.to_representation(Representation::Description), "Cleanup / Performance");
+        assert_eq!(CommitType::from_representation("Bugfix", Representation::Emoji), None);
+        assert_eq!(CommitType::from_representation("🐛", Representation::Name), None);
+    }
+
     #[test]
     fn it_gives_a_description() {
         assert_eq!(CommitType::Breaking.description(), "Breaking change");
@@ -0,0 +1,424 @@
+//! Linting commit messages against a set of configurable rules
+
+use std::fmt;
+use std::str::FromStr;
+
+use config::Config;
+use emoji::{leading_emoji_len, leading_shortcode_len};
+use error::{ParseError, ParseVariantError, Span};
+use message::{check_subject_length, parse_header};
+use CommitMessage;
+
+/// How serious a violation is, errors reject the message while warnings only report it
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    /// Return the name of the severity, e.g. "error"
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A lint rule
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Rule {
+    LeadingEmoji,
+    EmptySubject,
+    SubjectMaxLength,
+    TrailingPeriod,
+    ImperativeMood,
+    BlankLineAfterSubject,
+    Trailers,
+}
+
+/// All lint rules
+pub const RULES: [Rule; 7] = [
+    Rule::LeadingEmoji,
+    Rule::EmptySubject,
+    Rule::SubjectMaxLength,
+    Rule::TrailingPeriod,
+    Rule::ImperativeMood,
+    Rule::BlankLineAfterSubject,
+    Rule::Trailers,
+];
+
+impl Rule {
+    /// Return the id of the rule, as used in the `[lint]` configuration
+    pub fn id(&self) -> &'static str {
+        match *self {
+            Rule::LeadingEmoji => "leading-emoji",
+            Rule::EmptySubject => "empty-subject",
+            Rule::SubjectMaxLength => "subject-max-length",
+            Rule::TrailingPeriod => "trailing-period",
+            Rule::ImperativeMood => "imperative-mood",
+            Rule::BlankLineAfterSubject => "blank-line-after-subject",
+            Rule::Trailers => "trailers",
+        }
+    }
+
+    /// Return the severity of violations of the rule
+    pub fn severity(&self) -> Severity {
+        match *self {
+            Rule::TrailingPeriod => Severity::Warning,
+            Rule::ImperativeMood => Severity::Warning,
+            Rule::Trailers => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseVariantError;
+
+    fn from_str(s: &str) -> Result<Rule, ParseVariantError> {
+        RULES.iter().cloned().find(|rule| rule.id() == s).ok_or_else(|| {
+            ParseVariantError::new("lint rule", s, RULES.iter().map(Rule::id).collect())
+        })
+    }
+}
+
+/// A violation of a lint rule
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Violation {
+    pub rule: Rule,
+    pub severity: Severity,
+    /// The byte range in the message that violates the rule
+    pub span: Span,
+    pub message: String,
+}
+
+impl Violation {
+    fn new<S: Into<String>>(rule: Rule, span: Span, message: S) -> Violation {
+        Violation { rule, severity: rule.severity(), span, message: message.into() }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.rule, self.message)
+    }
+}
+
+/// Words ending in "ed" or "ing" that are fine at the start of a subject
+const IMPERATIVE_EXCEPTIONS: &[&str] = &["bring", "embed", "feed", "need", "ping", "seed", "shed", "speed", "spring", "string", "swing"];
+
+/// Verbs that are often written in the third person, e.g. "Adds"
+const COMMON_VERBS: &[&str] = &[
+    "add", "allow", "bump", "change", "clean", "drop", "fix", "implement", "improve", "make", "merge", "move", "refactor", "remove",
+    "rename", "replace", "support", "update", "use",
+];
+
+/// Guess whether a word is a verb in the imperative mood, e.g. "Add" rather than "Added" or "Adds"
+fn is_imperative(word: &str) -> bool {
+    let word = word.to_lowercase();
+
+    if IMPERATIVE_EXCEPTIONS.contains(&word.as_str()) {
+        return true;
+    }
+
+    if word.len() > 3 && (word.ends_with("ed") || word.ends_with("ing")) {
+        return false;
+    }
+
+    let stem = |suffix: &str| word.strip_suffix(suffix).is_some_and(|stem| COMMON_VERBS.contains(&stem));
+
+    !stem("s") && !stem("es")
+}
+
+/// Return the rule violated by a message that can't be parsed
+fn parse_violation(err: &ParseError) -> Violation {
+    let rule = match *err {
+        ParseError::MissingEmoji(_) | ParseError::UnknownEmoji(_) => Rule::LeadingEmoji,
+        ParseError::EmptySubject(_) => Rule::EmptySubject,
+        ParseError::SubjectTooLong { .. } => Rule::SubjectMaxLength,
+        ParseError::MalformedTrailer(_) => Rule::Trailers,
+    };
+
+    Violation::new(rule, err.span(), err.to_string())
+}
+
+/// Check a commit message against the rules that are enabled in the configuration
+///
+/// The message must start with one of the configured commit types. Messages that can't be parsed
+/// only report why, the other rules are checked on parsed messages.
+pub fn lint(input: &str, config: &Config) -> Vec<Violation> {
+    let enabled = |rule: Rule| !config.lint.disabled.iter().any(|id| id == rule.id());
+
+    let header_end = input.find('\n').unwrap_or(input.len());
+    let header = &input[..header_end];
+
+    let len = match parse_header(header, |header| config.types.match_prefix(header)) {
+        Ok((_, len)) => len,
+        Err(err) => return Some(parse_violation(&err)).into_iter().filter(|violation| enabled(violation.rule)).collect(),
+    };
+
+    let mut violations = Vec::new();
+    let subject = header[len..].trim();
+    let subject_start = header_end - header[len..].trim_start().len();
+    let subject_end = subject_start + subject.len();
+
+    if let Some(len) = leading_emoji_len(subject).or_else(|| leading_shortcode_len(subject)) {
+        violations.push(Violation::new(Rule::LeadingEmoji, Span::new(subject_start, subject_start + len), "subject starts with more than one emoji"));
+    }
+
+    if let Err(err) = check_subject_length(subject, subject_start, config.lint.max_subject_length) {
+        violations.push(parse_violation(&err));
+    }
+
+    if subject.ends_with('.') && !subject.ends_with("...") {
+        violations.push(Violation::new(Rule::TrailingPeriod, Span::new(subject_end - 1, subject_end), "subject ends with a period"));
+    }
+
+    let mut words = subject.split(' ').scan(0, |offset, word| {
+        let start = *offset;
+
+        *offset += word.len() + 1;
+        Some((start, word))
+    }).filter(|&(_, word)| !word.is_empty());
+
+    let word = match words.next() {
+        Some((_, scope)) if scope.ends_with(':') => words.next(),
+        word => word,
+    };
+
+    if let Some((offset, word)) = word {
+        if !is_imperative(word.trim_end_matches(|c: char| !c.is_alphanumeric())) {
+            let start = subject_start + offset;
+
+            violations.push(Violation::new(Rule::ImperativeMood, Span::new(start, start + word.len()), format!("subject should use the imperative mood, e.g. \"Add\" instead of \"{}\"", word)));
+        }
+    }
+
+    if let Some(line) = input[header_end..].split('\n').nth(1) {
+        if !line.trim().is_empty() {
+            let start = header_end + 1;
+
+            violations.push(Violation::new(Rule::BlankLineAfterSubject, Span::new(start, start + line.len()), "subject must be followed by a blank line"));
+        }
+    }
+
+    if let Err(err) = CommitMessage::check_trailers(input) {
+        violations.push(parse_violation(&err));
+    }
+
+    violations.retain(|violation| enabled(violation.rule));
+    violations
+}
+
+/// Check if any of the violations is an error
+pub fn has_errors(violations: &[Violation]) -> bool {
+    violations.iter().any(|violation| violation.severity == Severity::Error)
+}
+
+/// Repair the mechanical problems in a commit message
+///
+/// The leading emoji is written the way its type in the configuration does, e.g. `:bug:` or
+/// "🐛\u{fe0f}" become "🐛", followed by a single space. Trailing whitespace is trimmed from every
+/// line, and a blank line is inserted after the subject if it's missing. Fixes for rules that are
+/// disabled in the configuration are skipped.
+pub fn fix(input: &str, config: &Config) -> String {
+    let enabled = |rule: Rule| !config.lint.disabled.iter().any(|id| id == rule.id());
+    let mut lines: Vec<String> = input.split('\n').map(|line| String::from(line.trim_end())).collect();
+
+    if enabled(Rule::LeadingEmoji) {
+        if let Some((definition, len)) = config.types.match_prefix(&lines[0]) {
+            let subject = lines[0][len..].trim_start();
+
+            lines[0] = if subject.is_empty() { definition.emoji.clone() } else { format!("{} {}", definition.emoji, subject) };
+        }
+    }
+
+    if enabled(Rule::BlankLineAfterSubject) && lines.len() > 1 && !lines[1].is_empty() {
+        lines.insert(1, String::new());
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fix, has_errors, is_imperative, lint, Rule, Severity, RULES};
+    use config::{Config, LintConfig};
+    use error::Span;
+
+    fn rules(input: &str) -> Vec<Rule> {
+        lint(input, &Config::default()).into_iter().map(|violation| violation.rule).collect()
+    }
+
+    #[test]
+    fn it_parses_rules() {
+        for rule in RULES.iter() {
+            assert_eq!(rule.id().parse::<Rule>(), Ok(*rule));
+        }
+
+        assert_eq!("imperative".parse::<Rule>().unwrap_err().input(), "imperative");
+    }
+
+    #[test]
+    fn it_accepts_good_messages() {
+        assert_eq!(rules("🐛 Fix the parser\n"), vec![]);
+        assert_eq!(rules("🎉 Add parser: trailers\n\nWith a body\n\nRefs: #12\n"), vec![]);
+        assert_eq!(rules("🌹 Need more tests..."), vec![]);
+    }
+
+    #[test]
+    fn it_reports_parse_errors() {
+        let violations = lint("Fix the parser", &Config::default());
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, Rule::LeadingEmoji);
+        assert_eq!(violations[0].severity, Severity::Error);
+        assert_eq!(violations[0].span, Span::new(0, 0));
+        assert_eq!(violations[0].to_string(), "error[leading-emoji]: message doesn't start with a commit type emoji");
+
+        assert_eq!(rules("🐛 "), vec![Rule::EmptySubject]);
+    }
+
+    #[test]
+    fn it_warns_about_malformed_trailers() {
+        let violations = lint("🐛 Fix the parser\n\nSigned-off-by: Linus\nRefs: #1\nnot a trailer", &Config::default());
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, Rule::Trailers);
+        assert_eq!(violations[0].span, Span::new(51, 64));
+        assert!(!has_errors(&violations));
+    }
+
+    #[test]
+    fn it_accepts_configured_types() {
+        let config = Config::parse("[[type]]\nname = \"Security\"\nemoji = \"🔒\"\naliases = [\":lock:\"]\n").unwrap();
+
+        assert_eq!(lint("🔒 Escape input\n", &config), vec![]);
+        assert_eq!(lint(":lock: Escape input\n", &config), vec![]);
+        assert_eq!(rules("🔒 Escape input\n"), vec![Rule::LeadingEmoji]);
+        assert_eq!(lint("🔒 Escaped input\n", &config)[0].span, Span::new(5, 12));
+    }
+
+    #[test]
+    fn it_reports_more_than_one_emoji() {
+        let violations = lint("🐛 ✨ Fix the parser", &Config::default());
+
+        assert_eq!(violations[0].rule, Rule::LeadingEmoji);
+        assert_eq!(violations[0].span, Span::new(5, 8));
+        assert_eq!(rules("🐛 :sparkles: Fix the parser"), vec![Rule::LeadingEmoji]);
+    }
+
+    #[test]
+    fn it_reports_long_subjects() {
+        let config = Config { lint: LintConfig { max_subject_length: 10, ..LintConfig::default() }, ..Config::default() };
+        let violations = lint("🐛 Fix the parser", &config);
+
+        assert_eq!(violations[0].rule, Rule::SubjectMaxLength);
+        assert_eq!(violations[0].span, Span::new(15, 19));
+        assert_eq!(violations[0].message, "subject is longer than 10 characters");
+    }
+
+    #[test]
+    fn it_reports_trailing_periods() {
+        let violations = lint("🐛 Fix the parser.\n", &Config::default());
+
+        assert_eq!(violations[0].rule, Rule::TrailingPeriod);
+        assert_eq!(violations[0].severity, Severity::Warning);
+        assert_eq!(violations[0].span, Span::new(19, 20));
+        assert!(!has_errors(&violations));
+    }
+
+    #[test]
+    fn it_guesses_the_imperative_mood() {
+        assert!(is_imperative("Add"));
+        assert!(is_imperative("Embed"));
+        assert!(is_imperative("Process"));
+        assert!(!is_imperative("Added"));
+        assert!(!is_imperative("Adding"));
+        assert!(!is_imperative("Adds"));
+        assert!(!is_imperative("fixes"));
+
+        let violations = lint("🐛 parser: Fixed crash", &Config::default());
+
+        assert_eq!(violations[0].rule, Rule::ImperativeMood);
+        assert_eq!(violations[0].span, Span::new(13, 18));
+
+        let violations = lint("🐛 fixed: fixed crash", &Config::default());
+
+        assert_eq!(violations[0].rule, Rule::ImperativeMood);
+        assert_eq!(violations[0].span, Span::new(12, 17));
+    }
+
+    #[test]
+    fn it_reports_a_missing_blank_line() {
+        let violations = lint("🐛 Fix the parser\nIt crashed\n", &Config::default());
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, Rule::BlankLineAfterSubject);
+        assert_eq!(violations[0].span, Span::new(20, 30));
+        assert!(has_errors(&violations));
+    }
+
+    #[test]
+    fn it_skips_disabled_rules() {
+        let config = Config { lint: LintConfig { disabled: vec![String::from("imperative-mood"), String::from("leading-emoji")], ..LintConfig::default() }, ..Config::default() };
+
+        assert_eq!(lint("🐛 Fixed the parser", &config), vec![]);
+        assert_eq!(lint("Fixed the parser", &config), vec![]);
+    }
+
+    #[test]
+    fn it_fixes_the_leading_emoji() {
+        assert_eq!(fix("🐛\u{fe0f} Fix the parser\n", &Config::default()), "🐛 Fix the parser\n");
+        assert_eq!(fix(":sparkles: Fix the parser", &Config::default()), ":sparkles: Fix the parser");
+        assert_eq!(fix(":bug: Fix the parser", &Config::default()), "🐛 Fix the parser");
+        assert_eq!(fix(":boom:\tDrop support", &Config::default()), "💥 Drop support");
+        assert_eq!(fix("🐛   Fix the parser", &Config::default()), "🐛 Fix the parser");
+        assert_eq!(fix("🐛Fix the parser", &Config::default()), "🐛 Fix the parser");
+        assert_eq!(fix("🐛  ", &Config::default()), "🐛");
+    }
+
+    #[test]
+    fn it_fixes_configured_types() {
+        let config = Config::parse("[[type]]\nname = \"Security\"\nemoji = \"🔒\"\naliases = [\":lock:\"]\n").unwrap();
+
+        assert_eq!(fix(":lock:  Escape input", &config), "🔒 Escape input");
+        assert_eq!(fix(":lock:  Escape input", &Config::default()), ":lock:  Escape input");
+    }
+
+    #[test]
+    fn it_skips_fixes_for_disabled_rules() {
+        let config = Config { lint: LintConfig { disabled: vec![String::from("leading-emoji"), String::from("blank-line-after-subject")], ..LintConfig::default() }, ..Config::default() };
+
+        assert_eq!(fix(":bug:  Fix the parser \nIt crashed", &config), ":bug:  Fix the parser\nIt crashed");
+    }
+
+    #[test]
+    fn it_fixes_whitespace() {
+        assert_eq!(fix("🐛 Fix the parser  \nIt crashed \n\nRefs: #12\t\n", &Config::default()), "🐛 Fix the parser\n\nIt crashed\n\nRefs: #12\n");
+        assert_eq!(fix("🐛 Fix\n\nBREAKING CHANGE: output\n  differs \n", &Config::default()), "🐛 Fix\n\nBREAKING CHANGE: output\n  differs\n");
+        assert_eq!(fix("Fix the parser \n", &Config::default()), "Fix the parser\n");
+    }
+
+    #[test]
+    fn it_fixes_what_it_lints() {
+        let input = ":bug:  Fix the parser \nIt crashed";
+
+        assert_eq!(rules(input), vec![Rule::BlankLineAfterSubject]);
+        assert_eq!(rules(&fix(input, &Config::default())), vec![]);
+    }
+}
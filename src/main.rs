@@ -0,0 +1,340 @@
+extern crate emoji_commit_type;
+
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use emoji_commit_type::config::Config;
+use emoji_commit_type::lint::{self, Rule, Violation};
+use emoji_commit_type::{changelog, hook, picker, release, BumpLevel, CommitTypeSet, Trailer, Version};
+
+const USAGE: &str = "Usage: emoji-commit-type <command> [options]
+
+Commands:
+    list                              List the commit types
+    parse <message>                   Parse a commit message
+    lint [--fix] [<file>]             Lint a commit message file, or standard input
+    bump <version> <level>            Apply a bump level to a version
+    bump <version> --from-log         Apply the bump level of the commits since the last release tag
+    changelog [--keep-a-changelog]    Render a changelog for the commits since the last tag
+    hook commit-msg <file>            Repair and check a commit message file, as a git hook
+    hook prepare-commit-msg <file>    Pick a commit type and prepend its emoji, as a git hook
+    install-hook [<name>]             Install a git hook into the current repository
+
+Options:
+    --pre <tag>                       Bump to a pre-release, e.g. 2.0.0-rc.0
+    --title <title>                   Title of the changelog section, defaults to the next version
+    --date <date>                     Date of the release, defaults to today
+";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if let Err(message) = run(&args) {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some("list") => list(),
+        Some("parse") => parse(&args[1..]),
+        Some("lint") => run_lint(&args[1..]),
+        Some("bump") => bump(&args[1..]),
+        Some("changelog") => changelog(&args[1..]),
+        Some("hook") => run_hook(&args[1..]),
+        Some("install-hook") => install_hook(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") | None => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(format!("unknown command \"{}\"\n\n{}", command, USAGE)),
+    }
+}
+
+/// Command line arguments, split into positional arguments and options
+struct Args<'a> {
+    positional: Vec<&'a str>,
+    options: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Args<'a> {
+    /// Parse the arguments, accepting the given flags and options that take a value
+    fn parse(args: &'a [String], flags: &[&str], options: &[&str]) -> Result<Args<'a>, String> {
+        let mut result = Args { positional: Vec::new(), options: Vec::new() };
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if flags.contains(&arg.as_str()) {
+                result.options.push((arg.as_str(), ""));
+            } else if options.contains(&arg.as_str()) {
+                match iter.next() {
+                    Some(value) => result.options.push((arg.as_str(), value.as_str())),
+                    None => return Err(format!("missing value for {}", arg)),
+                }
+            } else if arg.starts_with("--") {
+                return Err(format!("unknown option {}", arg));
+            } else {
+                result.positional.push(arg.as_str());
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Return the value of the given option, or an empty string for a flag
+    fn option(&self, name: &str) -> Option<&'a str> {
+        self.options.iter().find(|&&(key, _)| key == name).map(|&(_, value)| value)
+    }
+}
+
+/// Return today's date as `YYYY-MM-DD`
+fn today() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0);
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Convert a number of days since 1970-01-01 into a year, month and day
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+
+    (era * 400 + year_of_era + if month <= 2 { 1 } else { 0 }, month, day)
+}
+
+/// Read the closest configuration file
+fn load_config() -> Result<Config, String> {
+    let dir = env::current_dir().map_err(|err| err.to_string())?;
+
+    Config::discover(dir).map_err(|err| format!("invalid configuration: {}", err))
+}
+
+fn list() -> Result<(), String> {
+    let config = load_config()?;
+
+    for definition in config.types.iter() {
+        println!("{}  {:<9}{:<8}{}", definition.emoji, definition.name, definition.bump_level.name(), definition.description);
+    }
+
+    Ok(())
+}
+
+fn parse(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[], &[])?;
+    let input = args.positional.join(" ");
+    let config = load_config()?;
+
+    let (definition, subject) = config.types.check(&input).map_err(|err| {
+        let span = err.span();
+
+        format!("{} (at bytes {}..{})", err, span.start, span.end)
+    })?;
+
+    println!("type: {} {}", definition.emoji, definition.name);
+    println!("subject: {}", subject);
+    println!("bump: {}", definition.bump_level_of(&input));
+
+    for trailer in Trailer::from_message(&input).iter() {
+        println!("trailer: {}", trailer);
+    }
+
+    Ok(())
+}
+
+/// Print the violations, failing if any of them is an error
+fn report(violations: &[Violation], types: &CommitTypeSet) -> Result<(), String> {
+    for violation in violations.iter() {
+        eprintln!("{} (at bytes {}..{})", violation, violation.span.start, violation.span.end);
+    }
+
+    if !lint::has_errors(violations) {
+        return Ok(());
+    }
+
+    if violations.iter().any(|violation| violation.rule == Rule::LeadingEmoji) {
+        Err(format!("invalid commit message\n\nStart the commit message with one of these emoji:\n\n{}", hook::valid_types(types)))
+    } else {
+        Err(String::from("invalid commit message"))
+    }
+}
+
+fn run_lint(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["--fix"], &[])?;
+    let config = load_config()?;
+    let fixing = args.option("--fix").is_some();
+
+    let violations = match args.positional.first() {
+        Some(&"-") | None => {
+            let mut message = String::new();
+
+            io::stdin().read_to_string(&mut message).map_err(|err| err.to_string())?;
+
+            // Print the repaired message, as there is no file to write it back to
+            if fixing {
+                message = lint::fix(&message, &config);
+                print!("{}", message);
+            }
+
+            lint::lint(&message, &config)
+        }
+        Some(file) => {
+            let mut message = fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err))?;
+
+            // Files may be written by git, e.g. .git/COMMIT_EDITMSG, so leave their comments alone
+            if fixing {
+                message = fix_file(file, &message, hook::fix(&message, &config))?;
+            }
+
+            hook::lint(&message, &config)
+        }
+    };
+
+    report(&violations, &config.types)
+}
+
+/// Write the repaired commit message back to its file, only when something changed
+fn fix_file(file: &str, message: &str, fixed: String) -> Result<String, String> {
+    if fixed != message {
+        fs::write(file, &fixed).map_err(|err| format!("{}: {}", file, err))?;
+    }
+
+    Ok(fixed)
+}
+
+fn bump(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["--from-log"], &["--pre"])?;
+
+    let version = match args.positional.first() {
+        Some(version) => Version::parse(version).map_err(|err| err.to_string())?,
+        None => return Err(String::from("missing version")),
+    };
+
+    let config = load_config()?;
+
+    let level = match (args.positional.get(1), args.option("--from-log")) {
+        (None, Some(_)) => release::next_release_with(".", &config).map_err(|err| err.to_string())?.bump_level,
+        (Some(level), None) => level.parse::<BumpLevel>().map_err(|err| err.to_string())?,
+        _ => return Err(String::from("expected either a bump level or --from-log")),
+    };
+
+    match args.option("--pre") {
+        Some(tag) => println!("{}", config.next_prerelease(level, &version, tag)),
+        None => println!("{}", config.next_version(level, &version)),
+    }
+
+    Ok(())
+}
+
+fn changelog(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["--keep-a-changelog"], &["--title", "--date"])?;
+
+    let config = load_config()?;
+    let release = release::next_release_with(".", &config).map_err(|err| err.to_string())?;
+    let commits = release::unreleased(".", &config).map_err(|err| err.to_string())?;
+    let version = release.next.to_string();
+    let date = args.option("--date").map_or_else(today, String::from);
+
+    if args.option("--keep-a-changelog").is_some() {
+        print!("{}", changelog::keep_a_changelog(args.option("--title").unwrap_or(&version), &date, &commits, &config.changelog_definitions()));
+    } else {
+        let title = args.option("--title").map_or_else(|| format!("{} ({})", version, date), String::from);
+
+        print!("{}", changelog::markdown(&title, &commits, &config.changelog_definitions()));
+    }
+
+    Ok(())
+}
+
+fn run_hook(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[], &[])?;
+
+    match (args.positional.first(), args.positional.get(1)) {
+        (Some(&"commit-msg"), Some(file)) => {
+            let message = fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err))?;
+
+            // Merges, reverts and fixups are written by git, and are left as they are
+            if hook::is_generated(&hook::strip_comments(&message)) {
+                return Ok(());
+            }
+
+            let config = load_config()?;
+            let message = fix_file(file, &message, hook::fix(&message, &config))?;
+
+            report(&hook::lint(&message, &config), &config.types)
+        }
+        (Some(&"prepare-commit-msg"), Some(file)) => {
+            // Messages for merges, squashes and amends already have a subject
+            if args.positional.len() > 2 && args.positional[2] != "message" && args.positional[2] != "template" {
+                return Ok(());
+            }
+
+            let message = fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err))?;
+
+            let config = load_config()?;
+
+            if config.types.classify(&message).is_some() {
+                return Ok(());
+            }
+
+            // Without a terminal there is no one to ask, leave the message as it is
+            match picker::pick(&config.types) {
+                Ok(Some(definition)) => fs::write(file, picker::prepend(&message, definition, &config.types)).map_err(|err| format!("{}: {}", file, err)),
+                Ok(None) => Err(String::from("no commit type picked")),
+                Err(_) => Ok(()),
+            }
+        }
+        (Some(name), _) => Err(format!("unknown hook \"{}\"", name)),
+        (None, _) => Err(String::from("missing hook name")),
+    }
+}
+
+fn install_hook(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[], &[])?;
+    let name = args.positional.first().cloned().unwrap_or("commit-msg");
+
+    if name != "commit-msg" && name != "prepare-commit-msg" {
+        return Err(format!("unknown hook \"{}\"", name));
+    }
+
+    let path = hook::install(".", name).map_err(|err| err.to_string())?;
+
+    println!("Installed {}", path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{civil_from_days, Args};
+
+    #[test]
+    fn it_converts_days_to_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_741), (2026, 10, 15));
+    }
+
+    #[test]
+    fn it_parses_arguments() {
+        let args: Vec<String> = ["1.2.3", "--pre", "rc", "--from-log"].iter().map(|arg| String::from(*arg)).collect();
+        let parsed = Args::parse(&args, &["--from-log"], &["--pre"]).unwrap();
+
+        assert_eq!(parsed.positional, vec!["1.2.3"]);
+        assert_eq!(parsed.option("--pre"), Some("rc"));
+        assert_eq!(parsed.option("--from-log"), Some(""));
+        assert_eq!(parsed.option("--date"), None);
+        assert!(Args::parse(&args[..2], &[], &["--pre"]).is_err());
+        assert!(Args::parse(&args, &[], &[]).is_err());
+    }
+}
@@ -0,0 +1,564 @@
+use std::fmt;
+
+use std::str::FromStr;
+
+use emoji::{leading_emoji_len, leading_shortcode_len, match_prefix};
+use error::{ParseError, Span};
+use {BumpLevel, CommitType};
+
+/// A `Key: value` trailer at the end of a commit message
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
+    separator: String,
+}
+
+impl Trailer {
+    /// Create a new trailer, rendered as `key: value`
+    pub fn new<K: Into<String>, V: Into<String>>(key: K, value: V) -> Trailer {
+        Trailer { key: key.into(), value: value.into(), separator: String::from(" ") }
+    }
+
+    /// Return the trailers at the end of a full commit message, the same ones `CommitMessage::parse` finds
+    ///
+    /// Unlike `CommitMessage::parse`, this works for any message, whatever its subject line.
+    pub fn from_message(message: &str) -> Vec<Trailer> {
+        let header_end = message.find('\n').unwrap_or(message.len());
+        let rest = &message[header_end..];
+
+        paragraphs(rest).last().and_then(|&(start, end)| parse_trailers(&rest[start..end])).unwrap_or_default()
+    }
+
+    /// Check if this is a `BREAKING CHANGE` or `Breaking-Change` trailer
+    pub fn is_breaking_change(&self) -> bool {
+        is_breaking_change_key(&self.key)
+    }
+
+    /// Parse a single `Key: value` line
+    fn parse(line: &str) -> Option<Trailer> {
+        let colon = line.find(':')?;
+        let key = &line[..colon];
+
+        if !is_trailer_key(key) {
+            return None;
+        }
+
+        let rest = &line[colon + 1..];
+        let value = rest.trim_start_matches([' ', '\t']);
+
+        Some(Trailer {
+            key: String::from(key),
+            value: String::from(value),
+            separator: String::from(&rest[..rest.len() - value.len()]),
+        })
+    }
+}
+
+impl fmt::Display for Trailer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}{}", self.key, self.separator, self.value)
+    }
+}
+
+/// Check if the text in front of a colon is a valid trailer key
+fn is_trailer_key(key: &str) -> bool {
+    if key == "BREAKING CHANGE" {
+        return true;
+    }
+
+    !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '-')
+}
+
+/// Check if a trailer key marks a breaking change, `BREAKING CHANGE` must be upper case
+fn is_breaking_change_key(key: &str) -> bool {
+    key == "BREAKING CHANGE" || key.eq_ignore_ascii_case("BREAKING-CHANGE")
+}
+
+/// Check if a line continues the value of the trailer above it
+fn is_continuation(line: &str) -> bool {
+    line.starts_with(' ') || line.starts_with('\t')
+}
+
+/// Parse the last paragraph of a message as a block of trailers
+///
+/// Like git, the paragraph is only a trailer block if every line is a trailer or continues one.
+fn parse_trailers(paragraph: &str) -> Option<Vec<Trailer>> {
+    let mut trailers: Vec<Trailer> = Vec::new();
+
+    for line in paragraph.split('\n') {
+        if !trailers.is_empty() && is_continuation(line) {
+            let trailer = trailers.last_mut().unwrap();
+
+            trailer.value.push('\n');
+            trailer.value.push_str(line);
+        } else {
+            trailers.push(Trailer::parse(line)?);
+        }
+    }
+
+    Some(trailers)
+}
+
+/// Return the byte range of each paragraph in the text after the header
+fn paragraphs(rest: &str) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for line in rest.split('\n') {
+        let start = offset;
+        let end = start + line.len();
+        offset = end + 1;
+
+        if line.trim().is_empty() {
+            result.extend(current.take());
+        } else {
+            current = Some((current.map_or(start, |(para_start, _)| para_start), end));
+        }
+    }
+
+    result.extend(current);
+    result
+}
+
+/// Return the end of the first line, before its line break, either `\n` or `\r\n`
+fn header_end(input: &str) -> usize {
+    let end = input.find('\n').unwrap_or(input.len());
+
+    if input[..end].ends_with('\r') { end - 1 } else { end }
+}
+
+/// Find the first line that breaks an almost complete trailer block at the end of a message
+///
+/// A last paragraph where more than half of the lines are trailers was probably meant as one.
+pub(crate) fn malformed_trailer(input: &str) -> Option<Span> {
+    let header_end = input.find('\n').unwrap_or(input.len());
+    let (start, end) = *paragraphs(&input[header_end..]).last()?;
+    let paragraph = &input[header_end + start..header_end + end];
+
+    let mut malformed = None;
+    let mut in_trailer = false;
+    let mut valid_lines = 0;
+    let mut offset = header_end + start;
+
+    for line in paragraph.split('\n') {
+        if (in_trailer && is_continuation(line)) || Trailer::parse(line).is_some() {
+            in_trailer = true;
+            valid_lines += 1;
+        } else {
+            in_trailer = false;
+            malformed = malformed.or(Some(Span::new(offset, offset + line.len())));
+        }
+
+        offset += line.len() + 1;
+    }
+
+    malformed.filter(|_| valid_lines * 2 > paragraph.split('\n').count())
+}
+
+/// Split the header of a message into its type and the length of the emoji, using `matcher` to recognize the emoji
+///
+/// Reports a missing or unknown emoji, and an empty subject, with the same spans for every kind of type.
+pub(crate) fn parse_header<T, F>(header: &str, matcher: F) -> Result<(T, usize), ParseError>
+where
+    F: FnOnce(&str) -> Option<(T, usize)>,
+{
+    let (found, len) = match matcher(header) {
+        Some(found) => found,
+        None => return Err(match leading_emoji_len(header).or_else(|| leading_shortcode_len(header)) {
+            Some(len) => ParseError::UnknownEmoji(Span::new(0, len)),
+            None => ParseError::MissingEmoji(Span::new(0, 0)),
+        }),
+    };
+
+    if header[len..].trim().is_empty() {
+        return Err(ParseError::EmptySubject(Span::new(len, header.len())));
+    }
+
+    Ok((found, len))
+}
+
+/// Check that a subject, starting at byte `start` of the message, is at most `max` characters long
+///
+/// The span of the error covers the characters past the limit.
+pub(crate) fn check_subject_length(subject: &str, start: usize, max: usize) -> Result<(), ParseError> {
+    let subject = subject.trim_end();
+
+    match subject.char_indices().nth(max) {
+        Some((index, _)) => Err(ParseError::SubjectTooLong { span: Span::new(start + index, start + subject.len()), max }),
+        None => Ok(()),
+    }
+}
+
+/// A full commit message, split into type, subject, body paragraphs and trailers
+///
+/// Parsing and then rendering a message with `Display` gives back the exact input.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CommitMessage {
+    pub commit_type: CommitType,
+    pub subject: String,
+    pub body: Vec<String>,
+    pub trailers: Vec<Trailer>,
+    emoji: String,
+    separator: String,
+    gaps: Vec<String>,
+    tail: String,
+}
+
+impl CommitMessage {
+    /// Create a new commit message with only a subject line
+    pub fn new<S: Into<String>>(commit_type: CommitType, subject: S) -> CommitMessage {
+        CommitMessage {
+            commit_type,
+            subject: subject.into(),
+            body: Vec::new(),
+            trailers: Vec::new(),
+            emoji: String::from(commit_type.emoji()),
+            separator: String::from(" "),
+            gaps: Vec::new(),
+            tail: String::from("\n"),
+        }
+    }
+
+    /// Parse a full commit message
+    pub fn parse(input: &str) -> Result<CommitMessage, ParseError> {
+        let (header, rest) = input.split_at(header_end(input));
+
+        let (commit_type, len) = parse_header(header, match_prefix)?;
+        let (emoji, after) = header.split_at(len);
+        let subject = after.trim_start();
+        let separator = &after[..after.len() - subject.len()];
+
+        let mut body = Vec::new();
+        let mut gaps = Vec::new();
+        let mut gap_start = 0;
+
+        for (para_start, para_end) in paragraphs(rest) {
+            gaps.push(String::from(&rest[gap_start..para_start]));
+            body.push(String::from(&rest[para_start..para_end]));
+            gap_start = para_end;
+        }
+
+        let trailers = body.last().and_then(|paragraph| parse_trailers(paragraph));
+
+        if trailers.is_some() {
+            body.pop();
+        }
+
+        Ok(CommitMessage {
+            commit_type,
+            subject: String::from(subject),
+            body,
+            trailers: trailers.unwrap_or_default(),
+            emoji: String::from(emoji),
+            separator: String::from(separator),
+            gaps,
+            tail: String::from(&rest[gap_start..]),
+        })
+    }
+
+    /// Parse a full commit message, rejecting subjects longer than `max` characters
+    pub fn parse_with_max_subject_length(input: &str, max: usize) -> Result<CommitMessage, ParseError> {
+        let message = CommitMessage::parse(input)?;
+
+        check_subject_length(&message.subject, header_end(input) - message.subject.len(), max)?;
+
+        Ok(message)
+    }
+
+    /// Check the trailer block of a full commit message, reporting the first line that breaks it
+    ///
+    /// `parse` keeps a last paragraph that isn't made of trailers only in the body. When more than
+    /// half of its lines are trailers it was probably meant as one, and this returns a
+    /// `MalformedTrailer` error for the first line that isn't.
+    pub fn check_trailers(input: &str) -> Result<(), ParseError> {
+        match malformed_trailer(input) {
+            Some(span) => Err(ParseError::MalformedTrailer(span)),
+            None => Ok(()),
+        }
+    }
+
+    /// Check if this commit is a breaking change, either by its type or by a `BREAKING CHANGE` trailer
+    pub fn is_breaking_change(&self) -> bool {
+        self.commit_type == CommitType::Breaking || self.trailers.iter().any(Trailer::is_breaking_change)
+    }
+
+    /// Return the bump level required by this commit, breaking change trailers always require a major bump
+    pub fn bump_level(&self) -> BumpLevel {
+        if self.is_breaking_change() {
+            BumpLevel::Major
+        } else {
+            self.commit_type.bump_level()
+        }
+    }
+
+    /// Return the value of the first trailer with the given key, compared case-insensitively
+    pub fn trailer(&self, key: &str) -> Option<&str> {
+        self.trailers.iter().find(|trailer| trailer.key.eq_ignore_ascii_case(key)).map(|trailer| trailer.value.as_str())
+    }
+
+    /// Return the emoji as it was written, unless the commit type has been changed since
+    fn emoji(&self) -> &str {
+        match match_prefix(&self.emoji) {
+            Some((commit_type, _)) if commit_type == self.commit_type => &self.emoji,
+            _ => self.commit_type.emoji(),
+        }
+    }
+
+    /// Return the whitespace in front of the paragraph at the given index
+    fn gap(&self, index: usize) -> &str {
+        self.gaps.get(index).map_or("\n\n", |gap| gap.as_str())
+    }
+}
+
+impl FromStr for CommitMessage {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<CommitMessage, ParseError> {
+        CommitMessage::parse(s)
+    }
+}
+
+impl fmt::Display for CommitMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}{}", self.emoji(), self.separator, self.subject)?;
+
+        for (index, paragraph) in self.body.iter().enumerate() {
+            write!(f, "{}{}", self.gap(index), paragraph)?;
+        }
+
+        if !self.trailers.is_empty() {
+            f.write_str(self.gap(self.body.len()))?;
+
+            for (index, trailer) in self.trailers.iter().enumerate() {
+                if index > 0 {
+                    f.write_str("\n")?;
+                }
+
+                write!(f, "{}", trailer)?;
+            }
+        }
+
+        f.write_str(&self.tail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{malformed_trailer, CommitMessage, Trailer};
+    use error::{ParseError, Span};
+    use {BumpLevel, CommitType};
+
+    #[test]
+    fn it_parses_a_subject_line() {
+        let message = CommitMessage::parse("🐛 Fix the parser").unwrap();
+
+        assert_eq!(message.commit_type, CommitType::Bugfix);
+        assert_eq!(message.subject, "Fix the parser");
+        assert!(message.body.is_empty());
+        assert!(message.trailers.is_empty());
+    }
+
+    #[test]
+    fn it_parses_body_and_trailers() {
+        let message = CommitMessage::parse("🎉 Add trailers\n\nFirst paragraph\nstill first\n\nSecond\n\nSigned-off-by: Linus <linus@example.com>\nRefs: #12\n").unwrap();
+
+        assert_eq!(message.commit_type, CommitType::Feature);
+        assert_eq!(message.subject, "Add trailers");
+        assert_eq!(message.body, vec!["First paragraph\nstill first", "Second"]);
+        assert_eq!(message.trailers, vec![Trailer::new("Signed-off-by", "Linus <linus@example.com>"), Trailer::new("Refs", "#12")]);
+        assert_eq!(message.trailer("signed-off-by"), Some("Linus <linus@example.com>"));
+    }
+
+    #[test]
+    fn it_parses_trailer_continuations() {
+        let message = CommitMessage::parse("💥 Drop support\n\nBREAKING CHANGE: the old API\n  is gone").unwrap();
+
+        assert!(message.body.is_empty());
+        assert_eq!(message.trailer("BREAKING CHANGE"), Some("the old API\n  is gone"));
+    }
+
+    #[test]
+    fn it_promotes_breaking_change_trailers() {
+        let message = CommitMessage::parse("🐛 Fix parser\n\nBreaking-Change: output differs").unwrap();
+
+        assert_eq!(message.commit_type, CommitType::Bugfix);
+        assert!(message.is_breaking_change());
+        assert_eq!(message.bump_level(), BumpLevel::Major);
+
+        let message = CommitMessage::parse("🎉 Add parser\n\nBREAKING CHANGE: new output").unwrap();
+
+        assert_eq!(message.bump_level(), BumpLevel::Major);
+
+        let message = CommitMessage::parse("🎉 Add parser\n\nRefs: #12").unwrap();
+
+        assert!(!message.is_breaking_change());
+        assert_eq!(message.bump_level(), BumpLevel::Minor);
+    }
+
+    #[test]
+    fn it_reads_trailers_from_any_message() {
+        let input = "🔒 Escape input\n\nBreaking-Change: none\n\nRefs: #12\nSigned-off-by: Linus\n";
+
+        assert_eq!(Trailer::from_message(input), vec![Trailer::new("Refs", "#12"), Trailer::new("Signed-off-by", "Linus")]);
+        assert_eq!(Trailer::from_message("🐛 Fix\n\nBREAKING CHANGE: output\n  differs"), CommitMessage::parse("🐛 Fix\n\nBREAKING CHANGE: output\n  differs").unwrap().trailers);
+        assert_eq!(Trailer::from_message("Refs: #12"), vec![]);
+    }
+
+    #[test]
+    fn it_only_reads_breaking_changes_from_trailers() {
+        let message = CommitMessage::parse("🐛 Fix parser\n\nBreaking-Change: none\n\nRefs: #12").unwrap();
+
+        assert!(!message.is_breaking_change());
+        assert_eq!(message.bump_level(), BumpLevel::Patch);
+    }
+
+    #[test]
+    fn it_keeps_prose_in_the_body() {
+        let message = CommitMessage::parse("🔥 Clean up\n\nNote: this is prose\nthat spans lines").unwrap();
+
+        assert_eq!(message.body, vec!["Note: this is prose\nthat spans lines"]);
+        assert!(message.trailers.is_empty());
+    }
+
+    #[test]
+    fn it_parses_alternate_emoji_forms() {
+        let message = CommitMessage::parse("🐛\u{fe0f}\u{a0}Fix it").unwrap();
+
+        assert_eq!(message.commit_type, CommitType::Bugfix);
+        assert_eq!(message.subject, "Fix it");
+
+        let message = CommitMessage::parse("🌹\u{200d} Update readme").unwrap();
+
+        assert_eq!(message.commit_type, CommitType::Meta);
+        assert_eq!(message.subject, "Update readme");
+    }
+
+    #[test]
+    fn it_parses_shortcodes() {
+        let input = ":bug: Fix it\n";
+        let message = CommitMessage::parse(input).unwrap();
+
+        assert_eq!(message.commit_type, CommitType::Bugfix);
+        assert_eq!(message.subject, "Fix it");
+        assert_eq!(message.to_string(), input);
+        assert_eq!(CommitMessage::parse(":rocket: Launch"), Err(ParseError::UnknownEmoji(Span::new(0, 8))));
+    }
+
+    #[test]
+    fn it_renders_the_new_emoji_after_a_type_change() {
+        let mut message = CommitMessage::parse("🐛\u{fe0f} Fix it").unwrap();
+
+        message.commit_type = CommitType::Breaking;
+
+        assert_eq!(message.to_string(), "💥 Fix it");
+    }
+
+    #[test]
+    fn it_gives_the_bump_level_for_many_messages() {
+        let messages = vec![
+            CommitMessage::parse("🐛 Fix a crash").unwrap(),
+            CommitMessage::parse("🎉 Add a feature").unwrap(),
+            CommitMessage::parse("🌹 Update readme").unwrap(),
+        ];
+
+        assert_eq!(messages[0].bump_level(), BumpLevel::Patch);
+        assert_eq!(BumpLevel::from_commits(&messages), BumpLevel::Minor);
+    }
+
+    #[test]
+    fn it_rejects_messages_without_a_type() {
+        assert_eq!(CommitMessage::parse("Fix the parser"), Err(ParseError::MissingEmoji(Span::new(0, 0))));
+        assert_eq!(CommitMessage::parse(""), Err(ParseError::MissingEmoji(Span::new(0, 0))));
+    }
+
+    #[test]
+    fn it_rejects_unknown_emoji() {
+        assert_eq!(CommitMessage::parse("🚀 Launch"), Err(ParseError::UnknownEmoji(Span::new(0, 4))));
+        assert_eq!(CommitMessage::parse("👩‍🚀 Launch"), Err(ParseError::UnknownEmoji(Span::new(0, 11))));
+        assert_eq!(CommitMessage::parse("♻\u{fe0f} Refactor").unwrap_err().span(), Span::new(0, 6));
+    }
+
+    #[test]
+    fn it_rejects_empty_subjects() {
+        assert_eq!(CommitMessage::parse("🐛"), Err(ParseError::EmptySubject(Span::new(4, 4))));
+        assert_eq!(CommitMessage::parse("🐛  \n\nBody"), Err(ParseError::EmptySubject(Span::new(4, 6))));
+    }
+
+    #[test]
+    fn it_keeps_carriage_returns_out_of_the_subject() {
+        let input = "🐛 Fix crash\r\n\r\nBody\r\n";
+        let message = CommitMessage::parse(input).unwrap();
+
+        assert_eq!(message.subject, "Fix crash");
+        assert_eq!(message.to_string(), input);
+        assert_eq!(CommitMessage::parse("🐛 Fix crash\r").unwrap().subject, "Fix crash");
+        assert_eq!(CommitMessage::parse("🐛 Fix crash\r").unwrap().to_string(), "🐛 Fix crash\r");
+        assert_eq!(CommitMessage::parse("🐛 \r\n"), Err(ParseError::EmptySubject(Span::new(4, 5))));
+
+        let err = CommitMessage::parse_with_max_subject_length(input, 5).unwrap_err();
+
+        assert_eq!(err, ParseError::SubjectTooLong { span: Span::new(10, 14), max: 5 });
+    }
+
+    #[test]
+    fn it_rejects_long_subjects() {
+        let err = CommitMessage::parse_with_max_subject_length("🎉 Add a very long subject\n", 10).unwrap_err();
+
+        assert_eq!(err, ParseError::SubjectTooLong { span: Span::new(15, 28), max: 10 });
+        assert!(CommitMessage::parse_with_max_subject_length("🎉 Add a short one", 50).is_ok());
+    }
+
+    #[test]
+    fn it_keeps_almost_trailers_in_the_body() {
+        let input = "🐛 Fix\n\nBody\n\nSigned-off-by: A\nnot a trailer\nRefs: #3\n";
+        let message = CommitMessage::parse(input).unwrap();
+
+        assert_eq!(message.body, vec!["Body", "Signed-off-by: A\nnot a trailer\nRefs: #3"]);
+        assert!(message.trailers.is_empty());
+        assert_eq!(message.to_string(), input);
+        assert_eq!(malformed_trailer(input), Some(Span::new(33, 46)));
+        assert_eq!(&input[33..46], "not a trailer");
+        assert_eq!(CommitMessage::check_trailers(input), Err(ParseError::MalformedTrailer(Span::new(33, 46))));
+        assert_eq!(CommitMessage::check_trailers("🐛 Fix\n\nSigned-off-by: A\nRefs: #3"), Ok(()));
+    }
+
+    #[test]
+    fn it_keeps_prose_with_colons_in_the_body() {
+        let input = "🐛 Fix parser\n\nBefore: it crashed\nAfter: it works\nwhich is nice\n";
+        let message = CommitMessage::parse(input).unwrap();
+
+        assert_eq!(message.body, vec!["Before: it crashed\nAfter: it works\nwhich is nice"]);
+        assert!(message.trailers.is_empty());
+        assert_eq!(malformed_trailer("🐛 Fix\n\nSigned-off-by: A\nRefs: #3"), None);
+        assert_eq!(malformed_trailer("🐛 Fix"), None);
+    }
+
+    #[test]
+    fn it_round_trips_messages() {
+        let inputs = [
+            "🌹 Update readme",
+            "🌹 Update readme\n",
+            "🐛\u{fe0f}  Fix\t\n\n\n  Body with indent\n \n\nKey:value\n\n\n",
+            "🎉 Add\n\nBody\n\nAcked-by:  Someone\n\tcontinued\nRefs: #1",
+            "💥 Remove\r\n\r\nBody\r\n",
+        ];
+
+        for input in inputs.iter() {
+            assert_eq!(CommitMessage::parse(input).unwrap().to_string(), *input);
+        }
+    }
+
+    #[test]
+    fn it_renders_new_messages() {
+        let mut message = CommitMessage::new(CommitType::Feature, "Add a model");
+
+        assert_eq!(message.to_string(), "🎉 Add a model\n");
+
+        message.body.push(String::from("With a body."));
+        message.trailers.push(Trailer::new("Refs", "#2"));
+
+        assert_eq!(message.to_string(), "🎉 Add a model\n\nWith a body.\n\nRefs: #2\n");
+    }
+}
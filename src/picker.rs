@@ -0,0 +1,238 @@
+//! An interactive terminal prompt for picking a commit type
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+use {CommitTypeSet, TypeDefinition};
+
+/// A key press in the picker
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Key {
+    Up,
+    Down,
+    Enter,
+    Digit(u8),
+    Cancel,
+    Other,
+}
+
+/// Decode the bytes read from a terminal in raw mode into key presses
+pub fn decode(input: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut index = 0;
+
+    while index < input.len() {
+        let (key, len) = match input[index..] {
+            [0x1b, b'[', b'A', ..] | [0x1b, b'O', b'A', ..] => (Key::Up, 3),
+            [0x1b, b'[', b'B', ..] | [0x1b, b'O', b'B', ..] => (Key::Down, 3),
+            [0x1b, b'[', _, ..] | [0x1b, b'O', _, ..] => (Key::Other, 3),
+            [0x1b, ..] | [0x03, ..] | [0x04, ..] | [b'q', ..] => (Key::Cancel, 1),
+            [b'k', ..] => (Key::Up, 1),
+            [b'j', ..] => (Key::Down, 1),
+            [b'\r', ..] | [b'\n', ..] | [b' ', ..] => (Key::Enter, 1),
+            [digit @ b'0'..=b'9', ..] => (Key::Digit(digit - b'0'), 1),
+            _ => (Key::Other, 1),
+        };
+
+        keys.push(key);
+        index += len;
+    }
+
+    keys
+}
+
+/// The result of handling a key press
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Outcome<'a> {
+    Pending,
+    Picked(&'a TypeDefinition),
+    Cancelled,
+}
+
+/// The state of the commit type picker
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Picker<'a> {
+    types: &'a CommitTypeSet,
+    selected: usize,
+}
+
+impl<'a> Picker<'a> {
+    /// Create a new picker for the types in the set, with the first one selected
+    pub fn new(types: &'a CommitTypeSet) -> Picker<'a> {
+        Picker { types, selected: 0 }
+    }
+
+    /// Return the currently selected commit type, `None` if the set is empty
+    pub fn selected(&self) -> Option<&'a TypeDefinition> {
+        self.types.iter().nth(self.selected)
+    }
+
+    /// Handle a key press, arrow keys move the selection and digits pick a type directly
+    pub fn handle(&mut self, key: Key) -> Outcome<'a> {
+        match key {
+            Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::Down => self.selected = (self.selected + 1).min(self.types.iter().len().saturating_sub(1)),
+            Key::Enter => {
+                if let Some(definition) = self.selected() {
+                    return Outcome::Picked(definition);
+                }
+            }
+            Key::Digit(digit) if digit > 0 => {
+                if let Some(definition) = self.types.iter().nth(digit as usize - 1) {
+                    return Outcome::Picked(definition);
+                }
+            }
+            Key::Cancel => return Outcome::Cancelled,
+            _ => {}
+        }
+
+        Outcome::Pending
+    }
+
+    /// Render the list of commit types, with a marker in front of the selected one
+    pub fn render(&self) -> String {
+        self.types.iter().enumerate().map(|(index, definition)| {
+            let marker = if index == self.selected { "❯" } else { " " };
+
+            format!("{} {}. {}  {}\n", marker, index + 1, definition.emoji, definition.description)
+        }).collect()
+    }
+}
+
+/// Prepend the emoji of a commit type to a message, unless it already starts with a type in the set
+pub fn prepend(message: &str, definition: &TypeDefinition, types: &CommitTypeSet) -> String {
+    if types.classify(message).is_some() {
+        return String::from(message);
+    }
+
+    format!("{} {}", definition.emoji, message)
+}
+
+/// Run stty on the terminal with the given arguments
+fn stty(tty: &File, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty").args(args).stdin(Stdio::from(tty.try_clone()?)).stderr(Stdio::inherit()).output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed"));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Let the user pick one of the types in the set on the controlling terminal
+///
+/// Returns `None` when the user cancels the prompt.
+pub fn pick(types: &CommitTypeSet) -> io::Result<Option<&TypeDefinition>> {
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+    let saved = stty(&tty, &["-g"])?;
+
+    stty(&tty, &["-icanon", "-echo", "min", "1"])?;
+
+    let result = run(&mut tty, types);
+
+    stty(&tty, &[&saved])?;
+    result
+}
+
+fn run<'a>(tty: &mut File, types: &'a CommitTypeSet) -> io::Result<Option<&'a TypeDefinition>> {
+    let mut picker = Picker::new(types);
+    let mut buffer = [0; 16];
+    let lines = types.iter().len();
+
+    write!(tty, "Pick a commit type:\n{}", picker.render())?;
+
+    loop {
+        let len = tty.read(&mut buffer)?;
+
+        if len == 0 {
+            return Ok(None);
+        }
+
+        for key in decode(&buffer[..len]) {
+            match picker.handle(key) {
+                Outcome::Pending => {}
+                Outcome::Picked(definition) => return Ok(Some(definition)),
+                Outcome::Cancelled => return Ok(None),
+            }
+        }
+
+        write!(tty, "\x1b[{}A\r\x1b[J{}", lines, picker.render())?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, prepend, Key, Outcome, Picker};
+    use CommitTypeSet;
+
+    const SECURITY: &str = "[[type]]\nname = \"Security\"\nemoji = \"🔒\"\ndescription = \"Security fix\"\n";
+
+    #[test]
+    fn it_decodes_keys() {
+        assert_eq!(decode(b"\x1b[A\x1b[Bjk\r3q"), vec![Key::Up, Key::Down, Key::Down, Key::Up, Key::Enter, Key::Digit(3), Key::Cancel]);
+        assert_eq!(decode(b"\x1b[Cx\x1b"), vec![Key::Other, Key::Other, Key::Cancel]);
+    }
+
+    #[test]
+    fn it_moves_the_selection() {
+        let types = CommitTypeSet::default();
+        let mut picker = Picker::new(&types);
+
+        assert_eq!(picker.handle(Key::Up), Outcome::Pending);
+        assert_eq!(picker.selected(), types.get("Breaking"));
+        assert_eq!(picker.handle(Key::Down), Outcome::Pending);
+        assert_eq!(picker.handle(Key::Down), Outcome::Pending);
+        assert_eq!(picker.selected(), types.get("Bugfix"));
+        assert_eq!(picker.handle(Key::Enter), Outcome::Picked(types.get("Bugfix").unwrap()));
+
+        for _ in 0..10 {
+            picker.handle(Key::Down);
+        }
+
+        assert_eq!(picker.selected(), types.get("Meta"));
+    }
+
+    #[test]
+    fn it_picks_by_digit() {
+        let types = CommitTypeSet::default();
+        let mut picker = Picker::new(&types);
+
+        assert_eq!(picker.handle(Key::Digit(0)), Outcome::Pending);
+        assert_eq!(picker.handle(Key::Digit(6)), Outcome::Pending);
+        assert_eq!(picker.handle(Key::Digit(5)), Outcome::Picked(types.get("Meta").unwrap()));
+        assert_eq!(picker.handle(Key::Cancel), Outcome::Cancelled);
+    }
+
+    #[test]
+    fn it_renders_the_choices() {
+        let types = CommitTypeSet::default();
+        let mut picker = Picker::new(&types);
+
+        picker.handle(Key::Down);
+
+        assert_eq!(picker.render(), "  1. 💥  Breaking change\n❯ 2. 🎉  New functionality\n  3. 🐛  Bugfix\n  4. 🔥  Cleanup / Performance\n  5. 🌹  Meta\n");
+    }
+
+    #[test]
+    fn it_offers_configured_types() {
+        let types = CommitTypeSet::parse(&format!("builtin = false\n\n{}", SECURITY)).unwrap();
+        let mut picker = Picker::new(&types);
+
+        assert_eq!(picker.render(), "❯ 1. 🔒  Security fix\n");
+        assert_eq!(picker.handle(Key::Enter), Outcome::Picked(types.get("Security").unwrap()));
+        assert_eq!(Picker::new(&CommitTypeSet::new()).handle(Key::Enter), Outcome::Pending);
+    }
+
+    #[test]
+    fn it_prepends_the_emoji() {
+        let types = CommitTypeSet::parse(SECURITY).unwrap();
+        let feature = types.get("Feature").unwrap();
+
+        assert_eq!(prepend("Add picker\n", feature, &types), "🎉 Add picker\n");
+        assert_eq!(prepend("\n# Please enter the commit message\n", types.get("Bugfix").unwrap(), &types), "🐛 \n# Please enter the commit message\n");
+        assert_eq!(prepend("🐛 Fix picker\n", feature, &types), "🐛 Fix picker\n");
+        assert_eq!(prepend("🔒 Escape input\n", feature, &types), "🔒 Escape input\n");
+        assert_eq!(prepend("Escape input\n", types.get("Security").unwrap(), &types), "🔒 Escape input\n");
+    }
+}
@@ -0,0 +1,282 @@
+use std::slice;
+
+use emoji::{base, leading_emoji_len, leading_shortcode_len};
+use error::{ConfigError, ParseError, ParseVariantError};
+use message::{parse_header, Trailer};
+use toml::{self, Table};
+use {BumpLevel, CommitType};
+
+/// A commit type in a `CommitTypeSet`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TypeDefinition {
+    pub name: String,
+    pub emoji: String,
+    pub description: String,
+    pub bump_level: BumpLevel,
+    /// Shortcodes and other emoji that also stand for this type
+    pub aliases: Vec<String>,
+}
+
+impl TypeDefinition {
+    /// Check if the given emoji or shortcode stands for this type
+    pub fn matches(&self, emoji: &str) -> bool {
+        let emoji = base(emoji);
+
+        base(&self.emoji) == emoji || self.aliases.iter().any(|alias| base(alias) == emoji)
+    }
+
+    /// Return the built-in commit type with the same name, if there is one
+    pub fn commit_type(&self) -> Option<CommitType> {
+        CommitType::iter_variants().find(|commit_type| commit_type.name().eq_ignore_ascii_case(&self.name))
+    }
+
+    /// Check if a message of this type is a breaking change, by its type or by a `BREAKING CHANGE` trailer
+    pub fn is_breaking_change(&self, message: &str) -> bool {
+        self.commit_type() == Some(CommitType::Breaking) || Trailer::from_message(message).iter().any(Trailer::is_breaking_change)
+    }
+
+    /// Return the bump level of a message of this type, breaking change trailers always require a major bump
+    pub fn bump_level_of(&self, message: &str) -> BumpLevel {
+        if self.is_breaking_change(message) {
+            BumpLevel::Major
+        } else {
+            self.bump_level
+        }
+    }
+
+    fn from_table(table: &Table) -> Result<TypeDefinition, ConfigError> {
+        let name = toml::string(table, "name")?.ok_or_else(|| ConfigError::new(None, "every type needs a \"name\""))?;
+        let err = |message: String| ConfigError::new(None, format!("type \"{}\": {}", name, message));
+
+        let emoji = toml::string(table, "emoji").map_err(|e| err(e.to_string()))?.ok_or_else(|| err(String::from("missing \"emoji\"")))?;
+        let description = toml::string(table, "description").map_err(|e| err(e.to_string()))?.unwrap_or("");
+        let aliases = toml::strings(table, "aliases").map_err(|e| err(e.to_string()))?.unwrap_or_default();
+
+        let bump_level = match toml::string(table, "bump").map_err(|e| err(e.to_string()))? {
+            Some(bump) => bump.parse().map_err(|e: ParseVariantError| err(e.to_string()))?,
+            None => BumpLevel::None,
+        };
+
+        Ok(TypeDefinition {
+            name: String::from(name),
+            emoji: String::from(emoji),
+            description: String::from(description),
+            bump_level,
+            aliases: aliases.into_iter().map(String::from).collect(),
+        })
+    }
+}
+
+impl From<CommitType> for TypeDefinition {
+    fn from(commit_type: CommitType) -> TypeDefinition {
+        TypeDefinition {
+            name: String::from(commit_type.name()),
+            emoji: String::from(commit_type.emoji()),
+            description: String::from(commit_type.description()),
+            bump_level: commit_type.bump_level(),
+            aliases: commit_type.shortcodes().iter().map(|shortcode| String::from(*shortcode)).collect(),
+        }
+    }
+}
+
+/// A set of commit types, the five `CommitType` variants by default
+///
+/// Sets can be loaded from a configuration file with `[[type]]` entries:
+///
+/// ```toml
+/// [[type]]
+/// name = "Security"
+/// emoji = "🔒"
+/// description = "Security fix"
+/// bump = "Patch"
+/// aliases = [":lock:"]
+/// ```
+///
+/// A type with the same name as an existing one replaces it. Set `builtin = false` at the top of
+/// the file to start from an empty set instead.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CommitTypeSet {
+    types: Vec<TypeDefinition>,
+}
+
+impl Default for CommitTypeSet {
+    fn default() -> CommitTypeSet {
+        CommitTypeSet { types: CommitType::iter_variants().map(TypeDefinition::from).collect() }
+    }
+}
+
+impl CommitTypeSet {
+    /// Create an empty set
+    pub fn new() -> CommitTypeSet {
+        CommitTypeSet { types: Vec::new() }
+    }
+
+    /// Parse a set from a configuration file
+    pub fn parse(input: &str) -> Result<CommitTypeSet, ConfigError> {
+        CommitTypeSet::from_table(&toml::parse(input)?)
+    }
+
+    pub(crate) fn from_table(table: &Table) -> Result<CommitTypeSet, ConfigError> {
+        let mut set = match toml::boolean(table, "builtin")? {
+            Some(false) => CommitTypeSet::new(),
+            _ => CommitTypeSet::default(),
+        };
+
+        for definition in toml::tables(table, "type")? {
+            set.insert(TypeDefinition::from_table(definition)?);
+        }
+
+        Ok(set)
+    }
+
+    /// Add a type to the set, replacing any type with the same name
+    pub fn insert(&mut self, definition: TypeDefinition) {
+        match self.types.iter_mut().find(|existing| existing.name.eq_ignore_ascii_case(&definition.name)) {
+            Some(existing) => *existing = definition,
+            None => self.types.push(definition),
+        }
+    }
+
+    /// Return the type with the given name, ignoring case
+    pub fn get(&self, name: &str) -> Option<&TypeDefinition> {
+        self.types.iter().find(|definition| definition.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Return the type with the given name for changing it, ignoring case
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut TypeDefinition> {
+        self.types.iter_mut().find(|definition| definition.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Return an iterator over the types, in the order they were added
+    pub fn iter(&self) -> slice::Iter<'_, TypeDefinition> {
+        self.types.iter()
+    }
+
+    /// Match the emoji or shortcode at the start of the input, returning its type and its length in bytes
+    pub(crate) fn match_prefix(&self, input: &str) -> Option<(&TypeDefinition, usize)> {
+        let len = leading_shortcode_len(input).or_else(|| leading_emoji_len(input))?;
+
+        self.types.iter().find(|definition| definition.matches(&input[..len])).map(|definition| (definition, len))
+    }
+
+    /// Parse the subject line of a commit message, returning its type and the remaining subject
+    pub fn classify<'a>(&self, message: &'a str) -> Option<(&TypeDefinition, &'a str)> {
+        let subject = message.lines().next().unwrap_or("").trim_start();
+
+        self.match_prefix(subject).map(|(definition, len)| (definition, subject[len..].trim()))
+    }
+
+    /// Parse the subject line of a commit message like `CommitMessage::parse` does, but with the types in this set
+    ///
+    /// Returns the type and the subject, or why the subject line doesn't start with one of the types.
+    pub fn check<'a>(&self, message: &'a str) -> Result<(&TypeDefinition, &'a str), ParseError> {
+        let header = message.split('\n').next().unwrap_or("");
+        let (definition, len) = parse_header(header, |header| self.match_prefix(header))?;
+
+        Ok((definition, header[len..].trim()))
+    }
+}
+
+impl<'a> IntoIterator for &'a CommitTypeSet {
+    type Item = &'a TypeDefinition;
+    type IntoIter = slice::Iter<'a, TypeDefinition>;
+
+    fn into_iter(self) -> slice::Iter<'a, TypeDefinition> {
+        self.types.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommitTypeSet, TypeDefinition};
+    use {BumpLevel, CommitType, ParseError, Span};
+
+    const SECURITY: &str = "[[type]]\nname = \"Security\"\nemoji = \"🔒\"\ndescription = \"Security fix\"\nbump = \"patch\"\naliases = [\":lock:\"]\n";
+
+    #[test]
+    fn it_defaults_to_the_builtin_types() {
+        let set = CommitTypeSet::default();
+
+        assert_eq!(set.iter().map(|definition| definition.emoji.as_str()).collect::<Vec<_>>(), vec!["💥", "🎉", "🐛", "🔥", "🌹"]);
+        assert_eq!(set.get("bugfix").unwrap().bump_level, BumpLevel::Patch);
+        assert_eq!(set.get("Breaking").unwrap().commit_type(), Some(CommitType::Breaking));
+    }
+
+    #[test]
+    fn it_classifies_messages() {
+        let set = CommitTypeSet::default();
+        let (definition, subject) = set.classify("🐛\u{fe0f} Fix parser\n\nBody").unwrap();
+
+        assert_eq!(definition.name, "Bugfix");
+        assert_eq!(subject, "Fix parser");
+        assert_eq!(set.classify(":collision: Drop parser").unwrap().0.name, "Breaking");
+        assert_eq!(set.classify("🔒 Escape input"), None);
+        assert_eq!(set.classify("Fix parser"), None);
+    }
+
+    #[test]
+    fn it_loads_extra_types() {
+        let set = CommitTypeSet::parse(SECURITY).unwrap();
+        let security = set.get("security").unwrap();
+
+        assert_eq!(set.iter().count(), 6);
+        assert_eq!(security.description, "Security fix");
+        assert_eq!(security.bump_level, BumpLevel::Patch);
+        assert_eq!(security.commit_type(), None);
+        assert_eq!(set.classify("🔒 Escape input").unwrap().0, security);
+        assert_eq!(set.classify(":lock: Escape input").unwrap().0, security);
+    }
+
+    #[test]
+    fn it_checks_messages() {
+        let set = CommitTypeSet::parse(SECURITY).unwrap();
+
+        assert_eq!(set.check("🔒 Escape input\n\nBody").unwrap(), (set.get("Security").unwrap(), "Escape input"));
+        assert_eq!(set.check("🐛 Fix parser").unwrap().0.name, "Bugfix");
+        assert_eq!(set.check("🚀 Launch"), Err(ParseError::UnknownEmoji(Span::new(0, 4))));
+        assert_eq!(set.check("Escape input"), Err(ParseError::MissingEmoji(Span::new(0, 0))));
+        assert_eq!(set.check("🔒 \n\nBody"), Err(ParseError::EmptySubject(Span::new(4, 5))));
+        assert_eq!(CommitTypeSet::default().check("🔒 Escape input"), Err(ParseError::UnknownEmoji(Span::new(0, 4))));
+    }
+
+    #[test]
+    fn it_gives_the_bump_level_of_messages() {
+        let set = CommitTypeSet::parse(SECURITY).unwrap();
+        let security = set.get("Security").unwrap();
+
+        assert_eq!(security.bump_level_of("🔒 Escape input"), BumpLevel::Patch);
+        assert_eq!(security.bump_level_of("🔒 Escape input\n\nBREAKING CHANGE: stricter"), BumpLevel::Major);
+        assert!(set.get("Breaking").unwrap().is_breaking_change("💥 Drop parser"));
+        assert!(!security.is_breaking_change("🔒 Escape input"));
+    }
+
+    #[test]
+    fn it_replaces_types() {
+        let set = CommitTypeSet::parse("builtin = true\n\n[[type]]\nname = \"Other\"\nemoji = \"♻️\"\nbump = \"None\"\n").unwrap();
+
+        assert_eq!(set.iter().count(), 5);
+        assert_eq!(set.get("Other").unwrap().bump_level, BumpLevel::None);
+        assert_eq!(set.classify("♻ Refactor").unwrap().0.name, "Other");
+        assert_eq!(set.classify("🔥 Refactor"), None);
+    }
+
+    #[test]
+    fn it_starts_from_an_empty_set() {
+        let set = CommitTypeSet::parse(&format!("builtin = false\n{}", SECURITY)).unwrap();
+
+        assert_eq!(set.iter().map(|definition| definition.name.as_str()).collect::<Vec<_>>(), vec!["Security"]);
+
+        let mut set = CommitTypeSet::new();
+
+        set.insert(TypeDefinition::from(CommitType::Meta));
+        assert_eq!(set.classify("🌹 Update readme").unwrap().0.name, "Meta");
+    }
+
+    #[test]
+    fn it_rejects_invalid_types() {
+        assert_eq!(CommitTypeSet::parse("[[type]]\nemoji = \"🔒\"\n").unwrap_err().to_string(), "every type needs a \"name\"");
+        assert_eq!(CommitTypeSet::parse("[[type]]\nname = \"Security\"\n").unwrap_err().to_string(), "type \"Security\": missing \"emoji\"");
+        assert_eq!(CommitTypeSet::parse("[[type]]\nname = \"Security\"\nemoji = \"🔒\"\nbump = \"Huge\"\n").unwrap_err().to_string(), "type \"Security\": unknown bump level \"Huge\", expected one of: Major, Minor, Patch, None");
+        assert_eq!(CommitTypeSet::parse("[type]\nname = \"Security\"\n").unwrap_err().to_string(), "\"type\" must be an array of tables, e.g. [[type]]");
+    }
+}
@@ -0,0 +1,200 @@
+//! Computing the next release of a repository from its history
+
+use std::io;
+use std::path::Path;
+
+use config::Config;
+use git::{self, Commit, Revisions};
+use {BumpLevel, Version};
+
+/// The next release of a repository
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Release {
+    /// The most recent release tag, if there is one
+    pub tag: Option<String>,
+    /// The version of the most recent release, `0.0.0` if there is none
+    pub previous: Version,
+    /// The version of the next release
+    pub next: Version,
+    /// The bump level between the two versions
+    pub bump_level: BumpLevel,
+    /// The commits since the most recent release that require a bump, newest first
+    pub commits: Vec<Commit>,
+}
+
+/// Parse the version from a tag name such as `v1.2.3` or `1.2.3`
+pub fn version_from_tag(tag: &str) -> Option<Version> {
+    Version::parse(tag.strip_prefix('v').unwrap_or(tag)).ok()
+}
+
+/// Return the semver tag with the highest version that is reachable from `HEAD`
+pub fn last_release<P: AsRef<Path>>(repo: P) -> io::Result<Option<(String, Version)>> {
+    let tags = git::git(repo, &["tag", "--merged", "HEAD"])?;
+
+    Ok(tags.lines().filter_map(|tag| version_from_tag(tag).map(|version| (String::from(tag), version))).max_by(|a, b| a.1.cmp(&b.1)))
+}
+
+/// Compute the next release from the commits since the most recent release
+pub fn next_release<P: AsRef<Path>>(repo: P) -> io::Result<Release> {
+    next_release_with(repo, &Config::default())
+}
+
+/// Return the revisions since the given release, or the whole history if there is none
+fn since(last: &Option<(String, Version)>) -> Revisions {
+    match *last {
+        Some((ref tag, _)) => Revisions::Between(tag.clone(), String::from("HEAD")),
+        None => Revisions::All,
+    }
+}
+
+/// Read all typed commits since the most recent release, newest first, including those that don't require a bump
+pub fn unreleased<P: AsRef<Path>>(repo: P, config: &Config) -> io::Result<Vec<Commit>> {
+    let last = last_release(&repo)?;
+
+    git::log_with(&repo, &since(&last), &config.types)
+}
+
+/// Compute the next release from the commits since the most recent release, following the project configuration
+pub fn next_release_with<P: AsRef<Path>>(repo: P, config: &Config) -> io::Result<Release> {
+    let last = last_release(&repo)?;
+
+    let commits: Vec<Commit> = git::log_with(&repo, &since(&last), &config.types)?.into_iter().filter(|commit| commit.bump_level() != BumpLevel::None).collect();
+    let bump_level = BumpLevel::from_commits(commits.iter().map(Commit::bump_level));
+
+    let (tag, previous) = match last {
+        Some((tag, version)) => (Some(tag), version),
+        None => (None, Version::new(0, 0, 0)),
+    };
+
+    Ok(Release { tag, next: config.next_version(bump_level, &previous), previous, bump_level, commits })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{last_release, next_release, next_release_with, unreleased, version_from_tag};
+    use config::Config;
+    use git::tests::{commit, repo, run};
+    use {BumpLevel, CommitType, Version};
+
+    #[test]
+    fn it_parses_versions_from_tags() {
+        assert_eq!(version_from_tag("v1.2.3"), Some(Version::new(1, 2, 3)));
+        assert_eq!(version_from_tag("1.2.3"), Some(Version::new(1, 2, 3)));
+        assert_eq!(version_from_tag("release-1"), None);
+    }
+
+    #[test]
+    fn it_finds_the_last_release() {
+        let path = repo("last-release");
+
+        commit(&path, "🎉 Add parser");
+        assert_eq!(last_release(&path).unwrap(), None);
+
+        run(&path, &["tag", "v1.9.0"]);
+        run(&path, &["tag", "v1.10.0"]);
+        run(&path, &["tag", "nightly"]);
+
+        assert_eq!(last_release(&path).unwrap(), Some((String::from("v1.10.0"), Version::new(1, 10, 0))));
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn it_computes_the_next_release() {
+        let path = repo("next-release");
+
+        commit(&path, "🎉 Add parser");
+        run(&path, &["tag", "v1.2.3"]);
+        commit(&path, "🐛 Fix parser");
+        commit(&path, "🌹 Update readme");
+        commit(&path, "🎉 Add formatter");
+
+        let release = next_release(&path).unwrap();
+
+        assert_eq!(release.tag, Some(String::from("v1.2.3")));
+        assert_eq!(release.previous, Version::new(1, 2, 3));
+        assert_eq!(release.next, Version::new(1, 3, 0));
+        assert_eq!(release.bump_level, BumpLevel::Minor);
+        assert_eq!(release.commits.iter().map(|commit| commit.subject.as_str()).collect::<Vec<_>>(), vec!["Add formatter", "Fix parser"]);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn it_computes_the_first_release() {
+        let path = repo("first-release");
+
+        commit(&path, "🎉 Add parser");
+
+        let release = next_release(&path).unwrap();
+
+        assert_eq!(release.tag, None);
+        assert_eq!(release.next, Version::new(0, 0, 1));
+        assert_eq!(release.commits.len(), 1);
+
+        let config = Config { initial_development: false, ..Config::default() };
+
+        assert_eq!(next_release_with(&path, &config).unwrap().next, Version::new(0, 1, 0));
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn it_bumps_breaking_change_trailers() {
+        let path = repo("breaking-trailers");
+
+        commit(&path, "🎉 Add parser");
+        run(&path, &["tag", "v1.2.3"]);
+        commit(&path, "🐛 Fix parser\n\nBreaking-Change: errors are reported with a span");
+
+        let release = next_release(&path).unwrap();
+
+        assert_eq!(release.next, Version::new(2, 0, 0));
+        assert_eq!(release.bump_level, BumpLevel::Major);
+        assert_eq!(release.commits[0].commit_type(), Some(CommitType::Bugfix));
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn it_bumps_configured_types() {
+        let path = repo("configured-types");
+
+        commit(&path, "🎉 Add parser");
+        run(&path, &["tag", "v1.2.3"]);
+        commit(&path, "🔒 Escape input");
+
+        let config = Config::parse("[[type]]\nname = \"Security\"\nemoji = \"🔒\"\nbump = \"Patch\"\n").unwrap();
+        let release = next_release_with(&path, &config).unwrap();
+
+        assert_eq!(release.next, Version::new(1, 2, 4));
+        assert_eq!(release.commits[0].definition.name, "Security");
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn it_follows_bump_overrides() {
+        let path = repo("bump-overrides");
+
+        commit(&path, "🎉 Add parser");
+        run(&path, &["tag", "v1.0.0"]);
+        commit(&path, "🔥 Clean up parser");
+        commit(&path, "🌹 Update docs");
+
+        let config = Config::parse("[bump]\nOther = \"None\"\nMeta = \"Patch\"\n").unwrap();
+        let release = next_release_with(&path, &config).unwrap();
+
+        assert_eq!(release.next, Version::new(1, 0, 1));
+        assert_eq!(release.commits.iter().map(|commit| commit.subject.as_str()).collect::<Vec<_>>(), vec!["Update docs"]);
+
+        let config = Config::parse("[bump]\nOther = \"None\"\n").unwrap();
+
+        assert_eq!(next_release_with(&path, &config).unwrap().bump_level, BumpLevel::None);
+        assert_eq!(unreleased(&path, &config).unwrap().iter().map(|commit| commit.subject.as_str()).collect::<Vec<_>>(), vec!["Update docs", "Clean up parser"]);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+//! Serde support for `CommitType` and `BumpLevel`, behind the `serde` feature
+//!
+//! Commit types are written by name by default, and read from either their name or their emoji.
+//! Use one of the modules below to pick another representation for a field:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct CachedCommit {
+//!     #[serde(with = "emoji_commit_type::serialize::emoji")]
+//!     commit_type: CommitType,
+//! }
+//! ```
+
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use {BumpLevel, CommitType, Representation};
+
+/// Reads a commit type written in a single representation, or by name or emoji when there is none
+struct CommitTypeVisitor(Option<Representation>);
+
+impl<'de> Visitor<'de> for CommitTypeVisitor {
+    type Value = CommitType;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(Representation::Emoji) => f.write_str("a commit type emoji"),
+            Some(Representation::Name) => f.write_str("a commit type name"),
+            Some(Representation::Description) => f.write_str("a commit type description"),
+            None => f.write_str("a commit type name or emoji"),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<CommitType, E> {
+        let commit_type = match self.0 {
+            Some(representation) => CommitType::from_representation(v, representation),
+            None => v.parse().ok(),
+        };
+
+        commit_type.ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+impl Serialize for CommitType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for CommitType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<CommitType, D::Error> {
+        deserializer.deserialize_str(CommitTypeVisitor(None))
+    }
+}
+
+struct BumpLevelVisitor;
+
+impl<'de> Visitor<'de> for BumpLevelVisitor {
+    type Value = BumpLevel;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a bump level, e.g. \"Minor\"")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<BumpLevel, E> {
+        v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+impl Serialize for BumpLevel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for BumpLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BumpLevel, D::Error> {
+        deserializer.deserialize_str(BumpLevelVisitor)
+    }
+}
+
+fn serialize<S: Serializer>(commit_type: &CommitType, representation: Representation, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(commit_type.to_representation(representation))
+}
+
+fn deserialize<'de, D: Deserializer<'de>>(deserializer: D, representation: Representation) -> Result<CommitType, D::Error> {
+    deserializer.deserialize_str(CommitTypeVisitor(Some(representation)))
+}
+
+/// Write a commit type as its emoji, e.g. "🐛"
+pub mod emoji {
+    use serde::{Deserializer, Serializer};
+
+    use {CommitType, Representation};
+
+    pub fn serialize<S: Serializer>(commit_type: &CommitType, serializer: S) -> Result<S::Ok, S::Error> {
+        super::serialize(commit_type, Representation::Emoji, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CommitType, D::Error> {
+        super::deserialize(deserializer, Representation::Emoji)
+    }
+}
+
+/// Write a commit type as its name, e.g. "Bugfix", and only accept names when reading
+pub mod name {
+    use serde::{Deserializer, Serializer};
+
+    use {CommitType, Representation};
+
+    pub fn serialize<S: Serializer>(commit_type: &CommitType, serializer: S) -> Result<S::Ok, S::Error> {
+        super::serialize(commit_type, Representation::Name, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CommitType, D::Error> {
+        super::deserialize(deserializer, Representation::Name)
+    }
+}
+
+/// Write a commit type as its description, e.g. "Cleanup / Performance"
+pub mod description {
+    use serde::{Deserializer, Serializer};
+
+    use {CommitType, Representation};
+
+    pub fn serialize<S: Serializer>(commit_type: &CommitType, serializer: S) -> Result<S::Ok, S::Error> {
+        super::serialize(commit_type, Representation::Description, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CommitType, D::Error> {
+        super::deserialize(deserializer, Representation::Description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{self, Value};
+
+    use super::{description, emoji, name};
+    use {BumpLevel, CommitType};
+
+    #[test]
+    fn it_serializes_by_name() {
+        assert_eq!(serde_json::to_string(&CommitType::Bugfix).unwrap(), "\"Bugfix\"");
+        assert_eq!(serde_json::to_string(&BumpLevel::Minor).unwrap(), "\"Minor\"");
+        assert_eq!(serde_json::to_string(&vec![CommitType::Breaking, CommitType::Meta]).unwrap(), "[\"Breaking\",\"Meta\"]");
+    }
+
+    #[test]
+    fn it_deserializes_names_and_emoji() {
+        assert_eq!(serde_json::from_str::<CommitType>("\"Bugfix\"").unwrap(), CommitType::Bugfix);
+        assert_eq!(serde_json::from_str::<CommitType>("\"feature\"").unwrap(), CommitType::Feature);
+        assert_eq!(serde_json::from_str::<CommitType>("\"🌹\"").unwrap(), CommitType::Meta);
+        assert_eq!(serde_json::from_str::<BumpLevel>("\"patch\"").unwrap(), BumpLevel::Patch);
+        assert!(serde_json::from_str::<CommitType>("\"Docs\"").unwrap_err().to_string().starts_with("invalid value: string \"Docs\", expected a commit type name or emoji"));
+        assert!(serde_json::from_str::<BumpLevel>("\"Huge\"").is_err());
+        assert!(serde_json::from_str::<CommitType>("1").is_err());
+    }
+
+    #[test]
+    fn it_uses_the_chosen_representation() {
+        assert_eq!(emoji::serialize(&CommitType::Bugfix, serde_json::value::Serializer).unwrap(), Value::from("🐛"));
+        assert_eq!(name::serialize(&CommitType::Bugfix, serde_json::value::Serializer).unwrap(), Value::from("Bugfix"));
+        assert_eq!(description::serialize(&CommitType::Other, serde_json::value::Serializer).unwrap(), Value::from("Cleanup / Performance"));
+
+        assert_eq!(emoji::deserialize(Value::from("🐛\u{fe0f}")).unwrap(), CommitType::Bugfix);
+        assert_eq!(description::deserialize(Value::from("Cleanup / Performance")).unwrap(), CommitType::Other);
+        assert!(emoji::deserialize(Value::from("Bugfix")).is_err());
+        assert!(name::deserialize(Value::from("🐛")).is_err());
+    }
+}
@@ -0,0 +1,363 @@
+//! A parser for the subset of TOML used by the configuration files
+//!
+//! Supported are comments, `key = value` pairs, `[table]` and `[[array]]` headers, strings,
+//! integers, booleans and arrays. Dotted keys, inline tables and dates are not.
+
+use error::ConfigError;
+
+/// A parsed value
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Table(Table),
+}
+
+/// A table of keys and values, in the order they were written
+pub type Table = Vec<(String, Value)>;
+
+/// Return the value of the given key in a table
+pub fn get<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    table.iter().find(|(name, _)| name == key).map(|(_, value)| value)
+}
+
+/// Return the string value of the given key, if it is set
+pub fn string<'a>(table: &'a Table, key: &str) -> Result<Option<&'a str>, ConfigError> {
+    match get(table, key) {
+        None => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err(ConfigError::new(None, format!("\"{}\" must be a string", key))),
+    }
+}
+
+/// Return the boolean value of the given key, if it is set
+pub fn boolean(table: &Table, key: &str) -> Result<Option<bool>, ConfigError> {
+    match get(table, key) {
+        None => Ok(None),
+        Some(Value::Boolean(value)) => Ok(Some(*value)),
+        Some(_) => Err(ConfigError::new(None, format!("\"{}\" must be true or false", key))),
+    }
+}
+
+/// Return the array of strings value of the given key, if it is set
+pub fn strings<'a>(table: &'a Table, key: &str) -> Result<Option<Vec<&'a str>>, ConfigError> {
+    let err = || ConfigError::new(None, format!("\"{}\" must be an array of strings", key));
+
+    match get(table, key) {
+        None => Ok(None),
+        Some(Value::Array(values)) => values.iter().map(|value| match value {
+            Value::String(value) => Ok(value.as_str()),
+            _ => Err(err()),
+        }).collect::<Result<Vec<&str>, ConfigError>>().map(Some),
+        Some(_) => Err(err()),
+    }
+}
+
+/// Return the array of tables value of the given key, if it is set
+pub fn tables<'a>(table: &'a Table, key: &str) -> Result<Vec<&'a Table>, ConfigError> {
+    let err = || ConfigError::new(None, format!("\"{}\" must be an array of tables, e.g. [[{}]]", key, key));
+
+    match get(table, key) {
+        None => Ok(Vec::new()),
+        Some(Value::Array(values)) => values.iter().map(|value| match value {
+            Value::Table(table) => Ok(table),
+            _ => Err(err()),
+        }).collect(),
+        Some(_) => Err(err()),
+    }
+}
+
+/// Parse a document into its root table
+pub fn parse(input: &str) -> Result<Table, ConfigError> {
+    Parser { input, pos: 0 }.document()
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T, S: Into<String>>(&self, message: S) -> Result<T, ConfigError> {
+        Err(ConfigError::new(Some(self.input[..self.pos].matches('\n').count() + 1), message))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Skip spaces and tabs, and optionally newlines and comments
+    fn skip(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\r' => self.pos += 1,
+                '\n' if newlines => self.pos += 1,
+                '#' if newlines => self.pos += self.input[self.pos..].find('\n').unwrap_or(self.input.len() - self.pos),
+                _ => break,
+            }
+        }
+    }
+
+    /// Expect the end of a line, allowing a trailing comment
+    fn end_of_line(&mut self) -> Result<(), ConfigError> {
+        self.skip(false);
+
+        if self.peek() == Some('#') {
+            self.pos += self.input[self.pos..].find('\n').unwrap_or(self.input.len() - self.pos);
+        }
+
+        match self.peek() {
+            None | Some('\n') => Ok(()),
+            Some(c) => self.error(format!("unexpected \"{}\"", c)),
+        }
+    }
+
+    fn document(&mut self) -> Result<Table, ConfigError> {
+        let mut root = Table::new();
+        let mut current: Option<String> = None;
+
+        loop {
+            self.skip(true);
+
+            if self.peek().is_none() {
+                return Ok(root);
+            }
+
+            if self.eat('[') {
+                let array = self.eat('[');
+
+                self.skip(false);
+                let name = self.key()?;
+                self.skip(false);
+
+                if !self.eat(']') || (array && !self.eat(']')) {
+                    return self.error("expected \"]\" after the table name");
+                }
+
+                self.end_of_line()?;
+
+                match (get(&root, &name), array) {
+                    (None, false) => root.push((name.clone(), Value::Table(Table::new()))),
+                    (None, true) => root.push((name.clone(), Value::Array(Vec::new()))),
+                    (Some(Value::Array(_)), true) => {}
+                    _ => return self.error(format!("\"{}\" is defined twice", name)),
+                }
+
+                if array {
+                    if let Some((_, Value::Array(tables))) = root.iter_mut().find(|entry| entry.0 == name) {
+                        tables.push(Value::Table(Table::new()));
+                    }
+                }
+
+                current = Some(name);
+                continue;
+            }
+
+            let key = self.key()?;
+            self.skip(false);
+
+            if !self.eat('=') {
+                return self.error(format!("expected \"=\" after \"{}\"", key));
+            }
+
+            self.skip(false);
+            let value = self.value()?;
+            self.end_of_line()?;
+
+            let table = match current {
+                None => &mut root,
+                Some(ref name) => match root.iter_mut().find(|entry| entry.0 == *name).map(|entry| &mut entry.1) {
+                    Some(Value::Table(table)) => table,
+                    Some(Value::Array(tables)) => match tables.last_mut() {
+                        Some(Value::Table(table)) => table,
+                        _ => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                },
+            };
+
+            if get(table, &key).is_some() {
+                return self.error(format!("\"{}\" is defined twice", key));
+            }
+
+            table.push((key, value));
+        }
+    }
+
+    fn key(&mut self) -> Result<String, ConfigError> {
+        match self.peek() {
+            Some('"') | Some('\'') => self.string(),
+            _ => {
+                let len = self.input[self.pos..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-')).unwrap_or(self.input.len() - self.pos);
+
+                if len == 0 {
+                    return self.error("expected a key");
+                }
+
+                self.pos += len;
+                Ok(String::from(&self.input[self.pos - len..self.pos]))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ConfigError> {
+        let literal = self.eat('\'');
+
+        if !literal && !self.eat('"') {
+            return self.error("expected a string");
+        }
+
+        let mut output = String::new();
+
+        loop {
+            let c = match self.peek() {
+                None | Some('\n') => return self.error("unterminated string"),
+                Some(c) => c,
+            };
+
+            self.pos += c.len_utf8();
+
+            match c {
+                '\'' if literal => return Ok(output),
+                '"' if !literal => return Ok(output),
+                '\\' if !literal => output.push(self.escape()?),
+                c => output.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, ConfigError> {
+        let c = self.peek();
+        self.pos += c.map_or(0, char::len_utf8);
+
+        match c {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some('"') => Ok('"'),
+            Some('\\') => Ok('\\'),
+            Some(u) if u == 'u' || u == 'U' => {
+                let len = if u == 'u' { 4 } else { 8 };
+                let hex = self.input.get(self.pos..self.pos + len).unwrap_or("");
+
+                match u32::from_str_radix(hex, 16).ok().and_then(::std::char::from_u32) {
+                    Some(c) if hex.len() == len => {
+                        self.pos += len;
+                        Ok(c)
+                    }
+                    _ => self.error("invalid unicode escape"),
+                }
+            }
+            _ => self.error("invalid escape"),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ConfigError> {
+        match self.peek() {
+            Some('"') | Some('\'') => self.string().map(Value::String),
+            Some('[') => {
+                self.pos += 1;
+                let mut values = Vec::new();
+
+                loop {
+                    self.skip(true);
+
+                    if self.eat(']') {
+                        return Ok(Value::Array(values));
+                    }
+
+                    values.push(self.value()?);
+                    self.skip(true);
+
+                    if !self.eat(',') && self.peek() != Some(']') {
+                        return self.error("expected \",\" or \"]\" in array");
+                    }
+                }
+            }
+            _ => {
+                let len = self.input[self.pos..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '+')).unwrap_or(self.input.len() - self.pos);
+                let word = &self.input[self.pos..self.pos + len];
+
+                let value = match word {
+                    "true" => Value::Boolean(true),
+                    "false" => Value::Boolean(false),
+                    _ => match word.replace('_', "").parse() {
+                        Ok(number) if !word.is_empty() => Value::Integer(number),
+                        _ => return self.error("expected a value"),
+                    },
+                };
+
+                self.pos += len;
+                Ok(value)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Value};
+
+    fn string(text: &str) -> Value {
+        Value::String(String::from(text))
+    }
+
+    #[test]
+    fn it_parses_keys_and_values() {
+        let table = parse("# Comment\nname = \"emoji\" # trailing\nliteral = 'C:\\path'\nescaped = \"a\\\"b\\u00e9\"\ncount = 1_000\nnegative = -3\nenabled = true\n\"quoted key\" = false\n").unwrap();
+
+        assert_eq!(table, vec![
+            (String::from("name"), string("emoji")),
+            (String::from("literal"), string("C:\\path")),
+            (String::from("escaped"), string("a\"bé")),
+            (String::from("count"), Value::Integer(1000)),
+            (String::from("negative"), Value::Integer(-3)),
+            (String::from("enabled"), Value::Boolean(true)),
+            (String::from("quoted key"), Value::Boolean(false)),
+        ]);
+    }
+
+    #[test]
+    fn it_parses_arrays() {
+        let table = parse("list = [\n  \"a\", # first\n  \"b\",\n]\nempty = []\nnested = [[1], [2, 3]]\n").unwrap();
+
+        assert_eq!(table[0].1, Value::Array(vec![string("a"), string("b")]));
+        assert_eq!(table[1].1, Value::Array(vec![]));
+        assert_eq!(table[2].1, Value::Array(vec![Value::Array(vec![Value::Integer(1)]), Value::Array(vec![Value::Integer(2), Value::Integer(3)])]));
+    }
+
+    #[test]
+    fn it_parses_tables() {
+        let table = parse("top = 1\n\n[bump]\nOther = \"None\"\n\n[[type]]\nname = \"A\"\n\n[[type]]\nname = \"B\"\n").unwrap();
+
+        assert_eq!(table, vec![
+            (String::from("top"), Value::Integer(1)),
+            (String::from("bump"), Value::Table(vec![(String::from("Other"), string("None"))])),
+            (String::from("type"), Value::Array(vec![
+                Value::Table(vec![(String::from("name"), string("A"))]),
+                Value::Table(vec![(String::from("name"), string("B"))]),
+            ])),
+        ]);
+    }
+
+    #[test]
+    fn it_reports_errors_with_a_line() {
+        assert_eq!(parse("a = 1\nb = \n").unwrap_err().to_string(), "line 2: expected a value");
+        assert_eq!(parse("a = 1\na = 2\n").unwrap_err().to_string(), "line 2: \"a\" is defined twice");
+        assert_eq!(parse("a = \"open\n").unwrap_err().to_string(), "line 1: unterminated string");
+        assert_eq!(parse("[table\n").unwrap_err().to_string(), "line 1: expected \"]\" after the table name");
+        assert_eq!(parse("a = 1 2\n").unwrap_err().to_string(), "line 1: unexpected \"2\"");
+        assert_eq!(parse("[a]\n[a]\n").unwrap_err().to_string(), "line 2: \"a\" is defined twice");
+    }
+}
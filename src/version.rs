@@ -0,0 +1,221 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use error::ParseVersionError;
+
+/// A single dot separated pre-release identifier, such as `rc` or `1` in `1.0.0-rc.1`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Identifier {
+    Numeric(u64),
+    AlphaNumeric(String),
+}
+
+impl Identifier {
+    fn parse(input: &str) -> Option<Identifier> {
+        if input.is_empty() || !input.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return None;
+        }
+
+        if input.chars().all(|c| c.is_ascii_digit()) {
+            parse_number(input).map(Identifier::Numeric)
+        } else {
+            Some(Identifier::AlphaNumeric(String::from(input)))
+        }
+    }
+}
+
+/// Numeric identifiers have lower precedence than alphanumeric ones
+impl Ord for Identifier {
+    fn cmp(&self, other: &Identifier) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::AlphaNumeric(_)) => Ordering::Less,
+            (Identifier::AlphaNumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::AlphaNumeric(a), Identifier::AlphaNumeric(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Identifier) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Identifier::Numeric(number) => write!(f, "{}", number),
+            Identifier::AlphaNumeric(ref text) => f.write_str(text),
+        }
+    }
+}
+
+/// Parse a number without leading zeroes
+fn parse_number(input: &str) -> Option<u64> {
+    if input.is_empty() || (input.len() > 1 && input.starts_with('0')) || !input.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    input.parse().ok()
+}
+
+/// A semantic version, e.g. `1.2.3-rc.1+build.5`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<Identifier>,
+    pub build: Vec<String>,
+}
+
+impl Version {
+    /// Create a new version without pre-release or build metadata
+    pub fn new(major: u64, minor: u64, patch: u64) -> Version {
+        Version { major, minor, patch, pre: Vec::new(), build: Vec::new() }
+    }
+
+    /// Parse a version from a string
+    pub fn parse(input: &str) -> Result<Version, ParseVersionError> {
+        let err = || ParseVersionError::new(input);
+
+        let (rest, build) = match input.find('+') {
+            Some(index) => (&input[..index], Some(&input[index + 1..])),
+            None => (input, None),
+        };
+
+        let (core, pre) = match rest.find('-') {
+            Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+            None => (rest, None),
+        };
+
+        let numbers = core.split('.').map(parse_number).collect::<Option<Vec<u64>>>().ok_or_else(err)?;
+
+        if numbers.len() != 3 {
+            return Err(err());
+        }
+
+        let pre = match pre {
+            Some(pre) => pre.split('.').map(Identifier::parse).collect::<Option<Vec<Identifier>>>().ok_or_else(err)?,
+            None => Vec::new(),
+        };
+
+        let build = match build {
+            Some(build) => build.split('.').map(|part| {
+                if part.is_empty() || !part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                    None
+                } else {
+                    Some(String::from(part))
+                }
+            }).collect::<Option<Vec<String>>>().ok_or_else(err)?,
+            None => Vec::new(),
+        };
+
+        Ok(Version { major: numbers[0], minor: numbers[1], patch: numbers[2], pre, build })
+    }
+
+    /// Return the release version of this version, without pre-release or build metadata
+    pub fn promote(&self) -> Version {
+        Version::new(self.major, self.minor, self.patch)
+    }
+
+    /// Check if this is a pre-release version
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Version, ParseVersionError> {
+        Version::parse(s)
+    }
+}
+
+/// Versions are ordered by semver precedence, build metadata is only used to break ties
+impl Ord for Version {
+    fn cmp(&self, other: &Version) -> Ordering {
+        let core = (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch));
+
+        let pre = match (self.pre.is_empty(), other.pre.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.pre.cmp(&other.pre),
+        };
+
+        core.then(pre).then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+
+        for (index, identifier) in self.pre.iter().enumerate() {
+            write!(f, "{}{}", if index == 0 { "-" } else { "." }, identifier)?;
+        }
+
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build.join("."))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Identifier, Version};
+
+    #[test]
+    fn it_parses_a_version() {
+        assert_eq!(Version::parse("1.2.3"), Ok(Version::new(1, 2, 3)));
+
+        let version = Version::parse("1.0.0-rc.1+build.5").unwrap();
+
+        assert_eq!(version.pre, vec![Identifier::AlphaNumeric(String::from("rc")), Identifier::Numeric(1)]);
+        assert_eq!(version.build, vec!["build", "5"]);
+        assert!(version.is_prerelease());
+    }
+
+    #[test]
+    fn it_promotes_a_prerelease() {
+        assert_eq!(Version::parse("2.0.0-rc.1+build.5").unwrap().promote(), Version::new(2, 0, 0));
+        assert_eq!(Version::new(1, 2, 3).promote(), Version::new(1, 2, 3));
+    }
+
+    #[test]
+    fn it_rejects_invalid_versions() {
+        for input in ["", "1", "1.2", "1.2.3.4", "01.2.3", "1.2.x", "1.2.3-", "1.2.3-rc..1", "1.2.3-01", "1.2.3+", "v1.2.3"].iter() {
+            assert!(Version::parse(input).is_err(), "{} should be invalid", input);
+        }
+
+        assert_eq!(Version::parse("1.x.3").unwrap_err().to_string(), "invalid version \"1.x.3\", expected major.minor.patch");
+    }
+
+    #[test]
+    fn it_displays_a_version() {
+        for input in ["0.1.0", "1.2.3-alpha", "1.0.0-rc.1+build.5", "2.0.0+20261015"].iter() {
+            assert_eq!(Version::parse(input).unwrap().to_string(), *input);
+        }
+    }
+
+    #[test]
+    fn it_orders_versions_by_precedence() {
+        let versions = ["1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-alpha.beta", "1.0.0-beta", "1.0.0-beta.2", "1.0.0-beta.11", "1.0.0-rc.1", "1.0.0", "1.0.1", "1.1.0", "2.0.0"];
+
+        for pair in versions.windows(2) {
+            assert!(Version::parse(pair[0]).unwrap() < Version::parse(pair[1]).unwrap(), "{} < {}", pair[0], pair[1]);
+        }
+    }
+}